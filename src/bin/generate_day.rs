@@ -83,13 +83,13 @@ mod tests {{
     }
 
     let new_match_arm = format!(
-        r#"        ({}, {}) => y{}::day{:02}::Day{:02}::new(input).run(),"#,
+        r#"        ({}, {}) => boxed::<y{}::day{:02}::Day{:02}>,"#,
         args.year, args.day, args.year, args.day, args.day
     );
 
     let mut main_content = fs::read_to_string(main_file).expect("Failed to read main.rs");
-    let insertion_marker =
-        "        _ => println!(\"No implementation for year {} day {}\", year, day),";
+    add_year(&mut main_content, args.year);
+    let insertion_marker = "        _ => return None,";
     if let Some(pos) = main_content.find(insertion_marker) {
         main_content.insert_str(pos, &format!("{}\n", new_match_arm));
         fs::write(main_file, main_content).expect("Failed to update main.rs");
@@ -103,3 +103,38 @@ mod tests {{
         args.day, day_file
     );
 }
+
+/// Declares the year's module and lists it in `YEARS` if it is new.
+fn add_year(main_content: &mut String, year: i32) {
+    let mod_entry = format!("pub mod y{};", year);
+    if !main_content.contains(&mod_entry) {
+        let last_year = main_content.rfind("pub mod y").unwrap_or(0);
+        let pos = main_content[last_year..]
+            .find('\n')
+            .map_or(main_content.len(), |end| last_year + end + 1);
+        main_content.insert_str(pos, &format!("{}\n", mod_entry));
+    }
+
+    let years_marker = "pub const YEARS: &[i32] = &[";
+    let Some(start) = main_content
+        .find(years_marker)
+        .map(|pos| pos + years_marker.len())
+    else {
+        eprintln!("Could not find YEARS in main.rs!");
+        return;
+    };
+    let end = start
+        + main_content[start..]
+            .find(']')
+            .expect("YEARS is not closed");
+    let mut years: Vec<i32> = main_content[start..end]
+        .split(',')
+        .filter_map(|year| year.trim().parse().ok())
+        .collect();
+    if !years.contains(&year) {
+        years.push(year);
+        years.sort_unstable();
+        let list = years.iter().map(i32::to_string).collect::<Vec<_>>();
+        main_content.replace_range(start..end, &list.join(", "));
+    }
+}
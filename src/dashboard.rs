@@ -0,0 +1,426 @@
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::utils::terminal::{with_cooked_terminal, RawTerminal, CTRL_C};
+use crate::utils::{example_path, input_path, read_expected, Timed};
+use crate::{solver, YEARS};
+
+/// How long a benchmark keeps repeating a day, and the most runs it does.
+const BENCHMARK_TIME: Duration = Duration::from_secs(2);
+const BENCHMARK_RUNS: u32 = 100;
+
+const ANSWER_WIDTH: usize = 20;
+
+const HELP: &str = "up/down day, left/right year, r run, b benchmark, i input, e example, q quit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Char(char),
+}
+
+/// Turns what one read from the terminal returned into keys, including
+/// the escape sequences of the arrow keys.
+fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let arrow = match bytes[i..] {
+            [0x1b, b'[', b'A', ..] => Some(Key::Up),
+            [0x1b, b'[', b'B', ..] => Some(Key::Down),
+            [0x1b, b'[', b'C', ..] => Some(Key::Right),
+            [0x1b, b'[', b'D', ..] => Some(Key::Left),
+            _ => None,
+        };
+
+        match arrow {
+            Some(key) => {
+                keys.push(key);
+                i += 3;
+            }
+            None => {
+                keys.push(match bytes[i] {
+                    b'\n' | b'\r' => Key::Enter,
+                    byte => Key::Char(byte as char),
+                });
+                i += 1;
+            }
+        }
+    }
+
+    keys
+}
+
+/// The answers of a run, with the mean time over all runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Outcome {
+    parts: [Timed; 2],
+    runs: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Status {
+    Idle,
+    Done(Outcome),
+    Failed(String),
+}
+
+fn truncate(answer: &str) -> String {
+    if answer.chars().count() > ANSWER_WIDTH {
+        let mut short: String = answer.chars().take(ANSWER_WIDTH - 3).collect();
+        short += "...";
+        short
+    } else {
+        answer.to_string()
+    }
+}
+
+fn check(answer: &str, expected: Option<&str>) -> &'static str {
+    match expected {
+        None => "?",
+        Some(expected) if expected == answer => "ok",
+        Some(_) => "WRONG",
+    }
+}
+
+struct Dashboard {
+    years: Vec<i32>,
+    year: usize,
+    day: i32,
+    results: HashMap<(i32, i32), Status>,
+    message: String,
+}
+
+impl Dashboard {
+    fn new(years: Vec<i32>, year: Option<i32>) -> Self {
+        assert!(!years.is_empty(), "the dashboard needs at least one year");
+        let year = year
+            .and_then(|year| years.iter().position(|&y| y == year))
+            .unwrap_or(years.len().saturating_sub(1));
+
+        Self {
+            years,
+            year,
+            day: 1,
+            results: HashMap::new(),
+            message: String::new(),
+        }
+    }
+
+    fn selected(&self) -> (i32, i32) {
+        (self.years[self.year], self.day)
+    }
+
+    fn render(&self) -> String {
+        let mut lines = vec!["Advent of Code".to_string(), String::new()];
+
+        let years = self
+            .years
+            .iter()
+            .enumerate()
+            .map(|(i, year)| {
+                if i == self.year {
+                    format!("\x1b[7m {} \x1b[0m", year)
+                } else {
+                    format!(" {} ", year)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        lines.push(years);
+        lines.push(String::new());
+
+        lines.push(format!(
+            "  Day  Code  Input  {:<w$}  {:>10}  {:<w$}  {:>10}  Expected",
+            "Part one",
+            "Time",
+            "Part two",
+            "Time",
+            w = ANSWER_WIDTH
+        ));
+
+        let year = self.years[self.year];
+        for day in 1..=25 {
+            lines.push(self.render_day(year, day));
+        }
+
+        lines.push(String::new());
+        lines.push(self.message.clone());
+        lines.push(HELP.to_string());
+
+        lines.join("\x1b[K\n") + "\x1b[K\x1b[J"
+    }
+
+    fn render_day(&self, year: i32, day: i32) -> String {
+        let implemented = solver(year, day).is_some();
+        let mark = |present: bool| if present { "*" } else { "-" };
+
+        let results = match self.results.get(&(year, day)).unwrap_or(&Status::Idle) {
+            Status::Idle => String::new(),
+            Status::Failed(err) => err.clone(),
+            Status::Done(outcome) => {
+                let expected = read_expected(year, day);
+                let [one, two] = &outcome.parts;
+                let runs = if outcome.runs > 1 {
+                    format!(" ({} runs)", outcome.runs)
+                } else {
+                    String::new()
+                };
+
+                format!(
+                    "{:<w$}  {:>10}  {:<w$}  {:>10}  {} {}{}",
+                    truncate(&one.answer),
+                    format!("{:.2?}", one.elapsed),
+                    truncate(&two.answer),
+                    format!("{:.2?}", two.elapsed),
+                    check(&one.answer, expected[0].as_deref()),
+                    check(&two.answer, expected[1].as_deref()),
+                    runs,
+                    w = ANSWER_WIDTH
+                )
+            }
+        };
+
+        let line = format!(
+            "  {:>3}   {}     {}    {}",
+            day,
+            mark(implemented),
+            mark(input_path(year, day).exists()),
+            results
+        );
+
+        if day == self.day {
+            format!("\x1b[7m{}\x1b[0m", line)
+        } else if !implemented {
+            format!("\x1b[2m{}\x1b[0m", line)
+        } else {
+            line
+        }
+    }
+
+    /// Runs the selected day `runs` times at most, stopping early once the
+    /// benchmark time is used up.
+    fn run(&mut self, runs: u32) {
+        let (year, day) = self.selected();
+        let Some(new) = solver(year, day) else {
+            self.message = format!("No implementation for year {} day {}", year, day);
+            return;
+        };
+        let Ok(input) = std::fs::read_to_string(input_path(year, day)) else {
+            self.message = format!("No input at {}", input_path(year, day).display());
+            return;
+        };
+
+        let status = match run_quietly(|| benchmark(new, &input, runs)) {
+            Ok(outcome) => Status::Done(outcome),
+            Err(err) => Status::Failed(format!("panicked: {}", err)),
+        };
+        self.message = format!("Ran year {} day {}", year, day);
+        self.results.insert((year, day), status);
+    }
+
+    fn page(&mut self, path: &Path) {
+        if !path.exists() {
+            self.message = format!("No file at {}", path.display());
+            return;
+        }
+
+        // The pager gets the normal screen and terminal settings while it runs.
+        let pager = std::env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+        let status = with_cooked_terminal(|| {
+            print!("\x1b[?25h\x1b[?1049l");
+            io::stdout().flush().unwrap();
+            let status = Command::new(&pager).arg(path).status();
+            print!("\x1b[?1049h\x1b[?25l\x1b[2J");
+            status
+        });
+        self.message = match status {
+            Ok(_) => String::new(),
+            Err(err) => format!("Could not open {}: {}", pager, err),
+        };
+    }
+
+    /// Handles a key, returning false when the dashboard should close.
+    fn handle(&mut self, key: Key) -> bool {
+        let (year, day) = self.selected();
+
+        match key {
+            Key::Up | Key::Char('k') => self.day = (self.day - 1).max(1),
+            Key::Down | Key::Char('j') => self.day = (self.day + 1).min(25),
+            Key::Left | Key::Char('h') => self.year = self.year.saturating_sub(1),
+            Key::Right | Key::Char('l') => self.year = (self.year + 1).min(self.years.len() - 1),
+            Key::Enter | Key::Char('r') => self.run(1),
+            Key::Char('b') => self.run(BENCHMARK_RUNS),
+            Key::Char('i') => self.page(&input_path(year, day)),
+            Key::Char('e') => self.page(&example_path(year, day)),
+            Key::Char('q') => return false,
+            Key::Char(c) if c == CTRL_C as char => return false,
+            Key::Char(_) => {}
+        }
+
+        true
+    }
+}
+
+fn benchmark(new: crate::Solver, input: &str, runs: u32) -> Outcome {
+    let start = Instant::now();
+    let mut total = [Duration::ZERO; 2];
+    let mut parts = None;
+    let mut done = 0;
+
+    while done < runs.max(1) && (done == 0 || start.elapsed() < BENCHMARK_TIME) {
+        let solved = new(input.to_string()).solve();
+        total[0] += solved[0].elapsed;
+        total[1] += solved[1].elapsed;
+        parts = Some(solved);
+        done += 1;
+    }
+
+    let [one, two] = parts.unwrap();
+    Outcome {
+        parts: [
+            Timed {
+                elapsed: total[0] / done,
+                ..one
+            },
+            Timed {
+                elapsed: total[1] / done,
+                ..two
+            },
+        ],
+        runs: done,
+    }
+}
+
+/// Runs `f`, turning a panic into its message instead of printing it over
+/// the dashboard.
+fn run_quietly<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    let message = Arc::new(Mutex::new(String::new()));
+    let hook = panic::take_hook();
+    let captured = Arc::clone(&message);
+    panic::set_hook(Box::new(move |info| {
+        *captured.lock().unwrap() = info.to_string();
+    }));
+
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    panic::set_hook(hook);
+
+    result.map_err(|_| message.lock().unwrap().clone())
+}
+
+/// Every year with at least one implemented day.
+fn years() -> Vec<i32> {
+    YEARS
+        .iter()
+        .copied()
+        .filter(|&year| (1..=25).any(|day| solver(year, day).is_some()))
+        .collect()
+}
+
+/// Opens the full-screen dashboard, starting on `year` if it has solutions.
+pub fn run(year: Option<i32>) {
+    let mut dashboard = Dashboard::new(years(), year);
+    let _terminal = RawTerminal::enable();
+    let mut stdout = io::stdout();
+    let mut buffer = [0; 16];
+
+    print!("\x1b[?1049h\x1b[?25l");
+
+    loop {
+        print!("\x1b[H{}", dashboard.render());
+        stdout.flush().unwrap();
+
+        let read = io::stdin().read(&mut buffer).unwrap_or(0);
+        if read == 0 {
+            break;
+        }
+
+        let keys = parse_keys(&buffer[..read]);
+        let running = keys
+            .iter()
+            .any(|key| matches!(key, Key::Enter | Key::Char('r') | Key::Char('b')));
+        if running {
+            dashboard.message = "Running...".to_string();
+            print!("\x1b[H{}", dashboard.render());
+            stdout.flush().unwrap();
+        }
+
+        if !keys.into_iter().all(|key| dashboard.handle(key)) {
+            break;
+        }
+    }
+
+    print!("\x1b[?25h\x1b[?1049l");
+    stdout.flush().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_arrow_keys() {
+        assert_eq!(
+            parse_keys(b"\x1b[Aj\x1b[Dq\r"),
+            [
+                Key::Up,
+                Key::Char('j'),
+                Key::Left,
+                Key::Char('q'),
+                Key::Enter
+            ]
+        );
+    }
+
+    #[test]
+    fn render_marks_selection_and_results() {
+        let mut dashboard = Dashboard::new(vec![2023, 2024], Some(2024));
+        dashboard.handle(Key::Down);
+        dashboard.results.insert(
+            (2024, 2),
+            Status::Done(Outcome {
+                parts: [
+                    Timed {
+                        answer: "2".to_string(),
+                        elapsed: Duration::from_millis(3),
+                    },
+                    Timed {
+                        answer: "a very long answer indeed".to_string(),
+                        elapsed: Duration::from_micros(5),
+                    },
+                ],
+                runs: 1,
+            }),
+        );
+        let screen = dashboard.render();
+
+        assert!(screen.contains("\x1b[7m 2024 \x1b[0m"));
+        let day = screen
+            .lines()
+            .find(|line| line.contains("\x1b[7m    2"))
+            .unwrap();
+        assert!(day.contains(&format!("{:<20}  {:>10}  ", "2", "3.00ms")));
+        assert!(day.contains("a very long answe..."));
+
+        dashboard.handle(Key::Left);
+        assert_eq!(dashboard.selected(), (2023, 2));
+        assert!(!dashboard.handle(Key::Char('q')));
+        assert!(!dashboard.handle(parse_keys(&[CTRL_C])[0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one year")]
+    fn needs_a_year() {
+        Dashboard::new(Vec::new(), None);
+    }
+}
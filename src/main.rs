@@ -1,9 +1,15 @@
+use crate::utils::export::save;
 use crate::utils::read_input;
-use crate::utils::AdventDay;
+use crate::utils::svg::Draw;
+use crate::utils::visualize::{play, Visualize};
+use crate::utils::{AdventDay, Solution};
 
 use clap::Parser;
+use std::path::PathBuf;
 
+pub mod dashboard;
 pub mod utils;
+pub mod watch;
 pub mod y2022;
 pub mod y2023;
 pub mod y2024;
@@ -11,50 +17,217 @@ pub mod y2024;
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
+    /// Without a day, the dashboard opens on this year
     #[arg(short, long)]
-    year: i32,
+    year: Option<i32>,
 
+    /// Without both year and day, the dashboard opens to pick one
     #[arg(short, long)]
-    day: i32,
+    day: Option<i32>,
+
+    /// Rebuild and rerun the day's tests and solution whenever its source,
+    /// the utils or its input change
+    #[arg(long)]
+    watch: bool,
+
+    /// Print the disassembled program (2024 day 17)
+    #[arg(long)]
+    disassemble: bool,
+
+    /// Print every executed instruction with the registers after it (2024 day 17),
+    /// or the stacks after every move (2022 day 5)
+    #[arg(long)]
+    trace: bool,
+
+    /// Crane model to trace the crate moves with, 9000 or 9001 (2022 day 5)
+    #[arg(long, default_value_t = 9000)]
+    crane: u32,
+
+    /// Step through the program interactively (2024 day 17)
+    #[arg(long)]
+    debug: bool,
+
+    /// Stop tracing before the instruction at this address, can be repeated
+    #[arg(long)]
+    breakpoint: Vec<usize>,
+
+    /// Stop tracing after this many instructions
+    #[arg(long)]
+    step_limit: Option<usize>,
+
+    /// Animate the simulation in the terminal (2024 days 6, 14, 15 and 18)
+    #[arg(long)]
+    visualize: bool,
+
+    /// Frames per second for --visualize and GIF export
+    #[arg(long, default_value_t = 10.0)]
+    fps: f64,
+
+    /// Save the simulation as an animated .gif, or its last frame as .png or .ppm.
+    /// A .svg gets a drawing of the answer instead (2024 days 10, 12, 16 and 18)
+    #[arg(long)]
+    export: Option<PathBuf>,
+
+    /// Pixels per tile for --export
+    #[arg(long, default_value_t = 4)]
+    scale: usize,
+}
+
+/// Plays the simulation in the terminal, or saves it when exporting.
+fn show(visualization: &impl Visualize, args: &Args) {
+    match &args.export {
+        Some(path) => match save(
+            visualization.frames(),
+            visualization.palette(),
+            args.scale,
+            args.fps,
+            path,
+        ) {
+            Ok(()) => println!("Saved {}", path.display()),
+            Err(err) => println!("Could not save {}: {}", path.display(), err),
+        },
+        None => play(visualization, args.fps),
+    }
 }
 
 fn main() {
     let args = Args::parse();
-    let (year, day) = (args.year, args.day);
+    let (Some(year), Some(day)) = (args.year, args.day) else {
+        let day_options = args.watch
+            || args.disassemble
+            || args.trace
+            || args.debug
+            || !args.breakpoint.is_empty()
+            || args.step_limit.is_some()
+            || args.visualize
+            || args.export.is_some();
+        if day_options {
+            println!(
+                "--watch, --trace, --export and the other day options need both --year and --day"
+            );
+        } else {
+            dashboard::run(args.year);
+        }
+        return;
+    };
+
+    if args.watch {
+        watch::run(year, day);
+        return;
+    }
+
     let input = read_input(year, day);
-    match (year, day) {
+
+    if let Some(path) = args
+        .export
+        .as_ref()
+        .filter(|path| path.extension() == Some("svg".as_ref()))
+    {
+        let svg = match (year, day) {
+            (2024, 10) => y2024::day10::Day10::new(input).svg(),
+            (2024, 12) => y2024::day12::Day12::new(input).svg(),
+            (2024, 16) => y2024::day16::Day16::new(input).svg(),
+            (2024, 18) => y2024::day18::Day18::new(input).svg(),
+            _ => {
+                println!("No drawing for year {} day {}", year, day);
+                return;
+            }
+        };
+        match std::fs::write(path, svg.to_string()) {
+            Ok(()) => println!("Saved {}", path.display()),
+            Err(err) => println!("Could not save {}: {}", path.display(), err),
+        }
+        return;
+    }
+
+    if args.visualize || args.export.is_some() {
+        match (year, day) {
+            (2024, 6) => show(&y2024::day06::Day06::new(input), &args),
+            (2024, 14) => show(&y2024::day14::Day14::new(input), &args),
+            (2024, 15) => show(&y2024::day15::Day15::new(input), &args),
+            (2024, 18) => show(&y2024::day18::Day18::new(input), &args),
+            _ => println!("No visualization for year {} day {}", year, day),
+        }
+        return;
+    }
+
+    if (year, day) == (2022, 5) && args.trace {
+        let day05 = y2022::day05::Day05::new(input);
+        match args.crane {
+            9000 => println!("{}", day05.trace(&y2022::day05::CrateMover9000)),
+            9001 => println!("{}", day05.trace(&y2022::day05::CrateMover9001)),
+            crane => println!("Unknown crane CrateMover {}", crane),
+        }
+        return;
+    }
+
+    if (year, day) == (2024, 17) && (args.disassemble || args.trace || args.debug) {
+        let day17 = y2024::day17::Day17::new(input);
+        if args.disassemble {
+            println!("{}", day17.disassembly());
+        }
+        if args.trace {
+            println!("{}", day17.trace(&args.breakpoint, args.step_limit));
+        }
+        if args.debug {
+            day17.debug(&args.breakpoint);
+        }
+        return;
+    }
+
+    match solver(year, day) {
+        Some(new) => new(input).run(),
+        None => println!("No implementation for year {} day {}", year, day),
+    }
+}
+
+/// Builds a day's solution from its input.
+pub type Solver = fn(String) -> Box<dyn Solution>;
+
+fn boxed<D: AdventDay + 'static>(input: String) -> Box<dyn Solution> {
+    Box::new(D::new(input))
+}
+
+/// Every year with a module. `generate_day` adds new years here.
+pub const YEARS: &[i32] = &[2022, 2023, 2024];
+
+/// Every implemented day. `generate_day` adds new days above the last arm.
+pub fn solver(year: i32, day: i32) -> Option<Solver> {
+    let solver: Solver = match (year, day) {
         // Advent of Code 2022
-        (2022, 1) => y2022::day01::Day01::new(input).run(),
-        (2022, 2) => y2022::day02::Day02::new(input).run(),
-        (2022, 3) => y2022::day03::Day03::new(input).run(),
-        (2022, 4) => y2022::day04::Day04::new(input).run(),
-        (2022, 5) => y2022::day05::Day05::new(input).run(),
-        (2022, 6) => y2022::day06::Day06::new(input).run(),
+        (2022, 1) => boxed::<y2022::day01::Day01>,
+        (2022, 2) => boxed::<y2022::day02::Day02>,
+        (2022, 3) => boxed::<y2022::day03::Day03>,
+        (2022, 4) => boxed::<y2022::day04::Day04>,
+        (2022, 5) => boxed::<y2022::day05::Day05>,
+        (2022, 6) => boxed::<y2022::day06::Day06>,
 
         // Advent of Code 2023
-        (2023, 1) => y2023::day01::Day01::new(input).run(),
-        (2023, 2) => y2023::day02::Day02::new(input).run(),
+        (2023, 1) => boxed::<y2023::day01::Day01>,
+        (2023, 2) => boxed::<y2023::day02::Day02>,
 
         // Advent of Code 2024
-        (2024, 1) => y2024::day01::Day01::new(input).run(),
-        (2024, 2) => y2024::day02::Day02::new(input).run(),
-        (2024, 3) => y2024::day03::Day03::new(input).run(),
-        (2024, 4) => y2024::day04::Day04::new(input).run(),
-        (2024, 5) => y2024::day05::Day05::new(input).run(),
-        (2024, 6) => y2024::day06::Day06::new(input).run(),
-        (2024, 7) => y2024::day07::Day07::new(input).run(),
-        (2024, 8) => y2024::day08::Day08::new(input).run(),
-        (2024, 9) => y2024::day09::Day09::new(input).run(),
-        (2024, 10) => y2024::day10::Day10::new(input).run(),
-        (2024, 11) => y2024::day11::Day11::new(input).run(),
-        (2024, 12) => y2024::day12::Day12::new(input).run(),
-        (2024, 13) => y2024::day13::Day13::new(input).run(),
-        (2024, 14) => y2024::day14::Day14::new(input).run(),
-        (2024, 15) => y2024::day15::Day15::new(input).run(),
-        (2024, 16) => y2024::day16::Day16::new(input).run(),
-        (2024, 17) => y2024::day17::Day17::new(input).run(),
-        (2024, 18) => y2024::day18::Day18::new(input).run(),
-        (2024, 19) => y2024::day19::Day19::new(input).run(),
-        _ => println!("No implementation for year {} day {}", year, day),
-    }
+        (2024, 1) => boxed::<y2024::day01::Day01>,
+        (2024, 2) => boxed::<y2024::day02::Day02>,
+        (2024, 3) => boxed::<y2024::day03::Day03>,
+        (2024, 4) => boxed::<y2024::day04::Day04>,
+        (2024, 5) => boxed::<y2024::day05::Day05>,
+        (2024, 6) => boxed::<y2024::day06::Day06>,
+        (2024, 7) => boxed::<y2024::day07::Day07>,
+        (2024, 8) => boxed::<y2024::day08::Day08>,
+        (2024, 9) => boxed::<y2024::day09::Day09>,
+        (2024, 10) => boxed::<y2024::day10::Day10>,
+        (2024, 11) => boxed::<y2024::day11::Day11>,
+        (2024, 12) => boxed::<y2024::day12::Day12>,
+        (2024, 13) => boxed::<y2024::day13::Day13>,
+        (2024, 14) => boxed::<y2024::day14::Day14>,
+        (2024, 15) => boxed::<y2024::day15::Day15>,
+        (2024, 16) => boxed::<y2024::day16::Day16>,
+        (2024, 17) => boxed::<y2024::day17::Day17>,
+        (2024, 18) => boxed::<y2024::day18::Day18>,
+        (2024, 19) => boxed::<y2024::day19::Day19>,
+        _ => return None,
+    };
+
+    Some(solver)
 }
@@ -0,0 +1,251 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use super::visualize::{color, Frame, Palette, Rgb};
+
+/// Colour of tiles the palette does not list.
+pub const BACKGROUND: Rgb = (15, 15, 35);
+
+/// A frame drawn with `scale` pixels per tile, each pixel an index into
+/// `colors`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub colors: Vec<Rgb>,
+    pub indices: Vec<u8>,
+}
+
+impl Image {
+    pub fn render(frame: &Frame, palette: &Palette, scale: usize) -> Self {
+        Self::render_with_colors(frame, palette, colors(palette), scale)
+    }
+
+    fn render_with_colors(
+        frame: &Frame,
+        palette: &Palette,
+        colors: Vec<Rgb>,
+        scale: usize,
+    ) -> Self {
+        let rows = frame.tiles.len();
+        let cols = frame.tiles.iter().map(Vec::len).max().unwrap_or(0);
+        let (width, height) = (cols * scale, rows * scale);
+        let mut indices = vec![0; width * height];
+
+        for (i, row) in frame.tiles.iter().enumerate() {
+            for (j, &tile) in row.iter().enumerate() {
+                let index = color(palette, tile)
+                    .and_then(|rgb| colors.iter().position(|&c| c == rgb))
+                    .unwrap_or(0);
+                for y in i * scale..(i + 1) * scale {
+                    indices[y * width + j * scale..y * width + (j + 1) * scale].fill(index as u8);
+                }
+            }
+        }
+
+        Self {
+            width,
+            height,
+            colors,
+            indices,
+        }
+    }
+
+    fn rgb_bytes(&self) -> Vec<u8> {
+        self.indices
+            .iter()
+            .flat_map(|&i| {
+                let (r, g, b) = self.colors[i as usize];
+                [r, g, b]
+            })
+            .collect()
+    }
+
+    /// Binary PPM, which needs no encoder at all.
+    pub fn write_ppm(&self, writer: &mut impl Write) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.rgb_bytes())
+    }
+
+    pub fn write_png(&self, writer: &mut impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.rgb_bytes()))
+            .map_err(io::Error::other)
+    }
+}
+
+/// The background followed by every palette colour, without duplicates.
+/// A GIF palette holds at most 256 colours.
+fn colors(palette: &Palette) -> Vec<Rgb> {
+    let mut colors = vec![BACKGROUND];
+    for &(_, rgb) in palette {
+        if !colors.contains(&rgb) {
+            colors.push(rgb);
+        }
+    }
+    assert!(colors.len() <= 256, "palette has more than 255 colours");
+
+    colors
+}
+
+/// Encodes the frames as a looping GIF showing each one for `delay`
+/// hundredths of a second. All frames share the palette's colours, so the
+/// GIF only needs one colour table.
+pub fn write_gif(
+    frames: impl Iterator<Item = Frame>,
+    palette: &Palette,
+    scale: usize,
+    delay: u16,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    let colors = colors(palette);
+    let table: Vec<u8> = colors.iter().flat_map(|&(r, g, b)| [r, g, b]).collect();
+    let images =
+        frames.map(|frame| Image::render_with_colors(&frame, palette, colors.clone(), scale));
+    let mut images = images.peekable();
+
+    let Some(first) = images.peek() else {
+        return Err(io::Error::other("no frames to export"));
+    };
+    let size = (first.width, first.height);
+    let (Ok(width), Ok(height)) = (u16::try_from(size.0), u16::try_from(size.1)) else {
+        return Err(io::Error::other(format!(
+            "{}x{} image is too large for a GIF",
+            size.0, size.1
+        )));
+    };
+    let mut encoder = gif::Encoder::new(writer, width, height, &table).map_err(io::Error::other)?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(io::Error::other)?;
+
+    for image in images {
+        if (image.width, image.height) != size {
+            return Err(io::Error::other(format!(
+                "frame is {}x{} but the first frame is {}x{}",
+                image.width, image.height, size.0, size.1
+            )));
+        }
+        let mut frame = gif::Frame::from_indexed_pixels(width, height, image.indices, None);
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(io::Error::other)?;
+    }
+
+    Ok(())
+}
+
+/// Saves a picture of the frames to `path`. A `.gif` gets every frame as an
+/// animation at `fps`, while `.png` and `.ppm` get the last frame only. The
+/// picture is encoded in memory first, so nothing is written if it fails.
+pub fn save(
+    frames: impl Iterator<Item = Frame>,
+    palette: &Palette,
+    scale: usize,
+    fps: f64,
+    path: &Path,
+) -> io::Result<()> {
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    if !["gif", "png", "ppm"].contains(&extension) {
+        return Err(io::Error::other(format!(
+            "unsupported image format {:?}, use gif, png or ppm",
+            extension
+        )));
+    }
+    let mut bytes = Vec::new();
+
+    if extension == "gif" {
+        let delay = (100.0 / fps.max(0.1)).round().max(1.0) as u16;
+        write_gif(frames, palette, scale, delay, &mut bytes)?;
+    } else {
+        let last = frames
+            .last()
+            .ok_or_else(|| io::Error::other("no frames to export"))?;
+        let image = Image::render(&last, palette, scale);
+        if extension == "png" {
+            image.write_png(&mut bytes)?;
+        } else {
+            image.write_ppm(&mut bytes)?;
+        }
+    }
+
+    fs::write(path, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PALETTE: &Palette = &[('#', (255, 0, 0)), ('@', (0, 0, 255))];
+
+    #[test]
+    fn render_ppm() {
+        let image = Image::render(&Frame::parse("#.\n.@", ""), PALETTE, 2);
+        let mut ppm = Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+
+        assert_eq!((image.width, image.height), (4, 4));
+        assert_eq!(
+            image.indices,
+            [1, 1, 0, 0, 1, 1, 0, 0, 0, 0, 2, 2, 0, 0, 2, 2]
+        );
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+        assert_eq!(ppm[11..14], [255, 0, 0]);
+        assert_eq!(ppm[17..20], [15, 15, 35]);
+    }
+
+    #[test]
+    fn png_and_gif_decode() {
+        let frames = [Frame::parse("#.\n.@", ""), Frame::parse(".#\n@.", "")];
+
+        let mut png = Vec::new();
+        Image::render(&frames[1], PALETTE, 3)
+            .write_png(&mut png)
+            .unwrap();
+        let reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (6, 6));
+
+        let mut gif = Vec::new();
+        write_gif(frames.into_iter(), PALETTE, 3, 10, &mut gif).unwrap();
+        let mut decoder = gif::DecodeOptions::new().read_info(gif.as_slice()).unwrap();
+        let mut count = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!((frame.width, frame.height, frame.delay), (6, 6, 10));
+            count += 1;
+        }
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn gif_rejects_bad_sizes() {
+        let frames = [Frame::parse("#.\n.@", ""), Frame::parse("#", "")];
+        let error = write_gif(frames.into_iter(), PALETTE, 1, 10, &mut Vec::new()).unwrap_err();
+        assert_eq!(error.to_string(), "frame is 1x1 but the first frame is 2x2");
+
+        let wide = Frame::parse(&"#".repeat(1 << 16), "");
+        let error = write_gif(std::iter::once(wide), PALETTE, 1, 10, &mut Vec::new()).unwrap_err();
+        assert_eq!(error.to_string(), "65536x1 image is too large for a GIF");
+    }
+
+    #[test]
+    fn failed_save_creates_no_file() {
+        let path = std::env::temp_dir().join(format!("aoc-export-{}.png", std::process::id()));
+        let error = save(std::iter::empty(), PALETTE, 1, 10.0, &path).unwrap_err();
+
+        assert_eq!(error.to_string(), "no frames to export");
+        assert!(!path.exists());
+
+        let path = path.with_extension("gif");
+        let wide = Frame::parse(&"#".repeat(1 << 16), "");
+        let error = save(std::iter::once(wide), PALETTE, 1, 10.0, &path).unwrap_err();
+
+        assert_eq!(error.to_string(), "65536x1 image is too large for a GIF");
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,255 @@
+use std::fmt;
+use std::ops::{Add, Sub};
+
+/// Integer types an interval can be built over.
+pub trait Bound: Copy + Ord + Default + Add<Output = Self> + Sub<Output = Self> {
+    /// The next value, used to turn an inclusive end into an exclusive one.
+    /// `None` for the largest value of the type.
+    fn successor(self) -> Option<Self>;
+}
+
+macro_rules! impl_bound {
+    ($($t:ty),*) => {
+        $(impl Bound for $t {
+            fn successor(self) -> Option<Self> {
+                self.checked_add(1)
+            }
+        })*
+    };
+}
+
+impl_bound!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// The values from `start` up to but not including `end`. An interval whose
+/// end is not after its start is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Bound> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    /// The values from `first` to `last`, both included. The exclusive end
+    /// must fit in `T`, so `last` cannot be the largest value of the type.
+    pub fn inclusive(first: T, last: T) -> Self {
+        let end = last
+            .successor()
+            .expect("an inclusive interval cannot end at the largest value of its type");
+        Self::new(first, end)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// Number of values in the interval.
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::default()
+        } else {
+            self.end - self.start
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Whether every value of `other` is also in this interval. The empty
+    /// interval is part of every interval.
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    /// Whether the two intervals share at least one value.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let intersection = Self::new(self.start.max(other.start), self.end.min(other.end));
+        (!intersection.is_empty()).then_some(intersection)
+    }
+
+    /// The parts of this interval outside `other`, in order. There are two
+    /// when `other` lies strictly inside.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if !self.overlaps(other) {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        }
+
+        [
+            Self::new(self.start, other.start),
+            Self::new(other.end, self.end),
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect()
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
+
+/// A union of intervals, kept sorted and merged so no two of them overlap
+/// or touch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Bound> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Bound> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Adds the interval, merging it with every interval it overlaps or
+    /// touches.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        let first = self
+            .intervals
+            .partition_point(|other| other.end < interval.start);
+        let last = self
+            .intervals
+            .partition_point(|other| other.start <= interval.end);
+
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, other| {
+                Interval::new(merged.start.min(other.start), merged.end.max(other.end))
+            });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, interval: Interval<T>) {
+        self.intervals = self
+            .intervals
+            .iter()
+            .flat_map(|other| other.difference(&interval))
+            .collect();
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self
+            .intervals
+            .partition_point(|interval| interval.end <= value);
+        self.intervals
+            .get(i)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of values covered by the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::default(), |len, interval| len + interval.len())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+}
+
+impl<T: Bound> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_operations() {
+        let a = Interval::inclusive(2, 8);
+        let b = Interval::new(5, 12);
+
+        assert_eq!(a, Interval::new(2, 9));
+        assert_eq!(a.len(), 7);
+        assert!(a.contains(8) && !a.contains(9));
+        assert!(a.overlaps(&b) && b.overlaps(&a));
+        assert!(!a.overlaps(&Interval::new(9, 10)));
+        assert!(a.contains_interval(&Interval::inclusive(3, 7)));
+        assert!(!a.contains_interval(&b));
+        assert_eq!(a.intersection(&b), Some(Interval::new(5, 9)));
+        assert_eq!(a.difference(&b), [Interval::new(2, 5)]);
+        assert_eq!(
+            a.difference(&Interval::new(4, 6)),
+            [Interval::new(2, 4), Interval::new(6, 9)]
+        );
+        assert!(a.difference(&Interval::new(0, 20)).is_empty());
+        assert_eq!(Interval::new(3, 1).len(), 0);
+        assert_eq!(b.to_string(), "[5, 12)");
+    }
+
+    #[test]
+    fn inclusive_up_to_near_max() {
+        assert_eq!(Interval::inclusive(0u8, 254).len(), 255);
+    }
+
+    #[test]
+    #[should_panic(expected = "largest value of its type")]
+    fn inclusive_rejects_max() {
+        Interval::inclusive(0u8, 255);
+    }
+
+    #[test]
+    fn interval_set_merges() {
+        let mut set: IntervalSet<i64> = [
+            Interval::new(10, 15),
+            Interval::new(1, 3),
+            Interval::new(3, 5),
+            Interval::new(12, 20),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            set.iter().copied().collect::<Vec<_>>(),
+            [Interval::new(1, 5), Interval::new(10, 20)]
+        );
+        assert_eq!(set.len(), 14);
+        assert!(set.contains(4) && !set.contains(5) && set.contains(19));
+
+        set.insert(Interval::new(4, 11));
+        assert_eq!(
+            set.iter().copied().collect::<Vec<_>>(),
+            [Interval::new(1, 20)]
+        );
+
+        set.remove(Interval::new(5, 10));
+        assert_eq!(
+            set.iter().copied().collect::<Vec<_>>(),
+            [Interval::new(1, 5), Interval::new(10, 20)]
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+pub mod export;
+pub mod interval;
+pub mod svg;
+pub mod terminal;
+pub mod visualize;
+
+pub trait AdventDay {
+    fn new(input: String) -> Self;
+    fn part_one(&self) -> String;
+    fn part_two(&self) -> String;
+    fn run(&self) {
+        let start_one = Instant::now();
+        let part_one = self.part_one();
+        let elapsed_one = start_one.elapsed();
+
+        if part_one.is_empty() {
+            println!("Part one not implemented");
+        } else {
+            println!("Part one: {}", part_one);
+            println!("Part one took {:?}", elapsed_one);
+        }
+
+        let start_two = Instant::now();
+        let part_two = self.part_two();
+        let elapsed_two = start_two.elapsed();
+
+        if part_two.is_empty() {
+            println!("Part two not implemented");
+        } else {
+            println!("Part two: {}", part_two);
+            println!("Part two took {:?}", elapsed_two);
+        }
+    }
+}
+
+/// An answer together with how long it took to compute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timed {
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+impl Timed {
+    pub fn measure(solve: impl FnOnce() -> String) -> Self {
+        let start = Instant::now();
+        let answer = solve();
+
+        Self {
+            answer,
+            elapsed: start.elapsed(),
+        }
+    }
+}
+
+/// Object-safe view of an `AdventDay`, so days of different types can be
+/// kept in one table.
+pub trait Solution {
+    fn run(&self);
+    fn solve(&self) -> [Timed; 2];
+}
+
+impl<T: AdventDay> Solution for T {
+    fn run(&self) {
+        AdventDay::run(self)
+    }
+
+    fn solve(&self) -> [Timed; 2] {
+        [
+            Timed::measure(|| self.part_one()),
+            Timed::measure(|| self.part_two()),
+        ]
+    }
+}
+
+pub fn input_path(year: i32, day: i32) -> PathBuf {
+    PathBuf::from(format!("data/inputs/{}/day{:02}.input.txt", year, day))
+}
+
+pub fn example_path(year: i32, day: i32) -> PathBuf {
+    PathBuf::from(format!("data/examples/{}/day{:02}.txt", year, day))
+}
+
+pub fn answers_path(year: i32, day: i32) -> PathBuf {
+    PathBuf::from(format!("data/answers/{}/day{:02}.txt", year, day))
+}
+
+pub fn read_input(year: i32, day: i32) -> String {
+    fs::read_to_string(input_path(year, day))
+        .unwrap_or_else(|_| panic!("Could not read file for year {} day {}", year, day))
+}
+
+/// Answers known to be right, stored as part one and part two on separate
+/// lines. Missing files and blank lines mean the answer is not known yet.
+pub fn read_expected(year: i32, day: i32) -> [Option<String>; 2] {
+    parse_expected(&fs::read_to_string(answers_path(year, day)).unwrap_or_default())
+}
+
+fn parse_expected(answers: &str) -> [Option<String>; 2] {
+    let mut lines = answers
+        .lines()
+        .map(|line| Some(line.trim().to_string()).filter(|line| !line.is_empty()));
+
+    [lines.next().flatten(), lines.next().flatten()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_expected_answers() {
+        assert_eq!(
+            parse_expected("41\n6\n"),
+            [Some("41".to_string()), Some("6".to_string())]
+        );
+        assert_eq!(parse_expected("\n6"), [None, Some("6".to_string())]);
+        assert_eq!(parse_expected(""), [None, None]);
+    }
+}
@@ -0,0 +1,183 @@
+use std::fmt;
+
+use super::export::BACKGROUND;
+use super::visualize::Rgb;
+
+/// A grid cell as (row, column). Corners between cells use the same
+/// coordinates, so the corner (r, c) is the top-left corner of cell (r, c).
+pub type Cell = (usize, usize);
+
+/// Vector drawing of a grid, built up in layers: later calls draw on top of
+/// earlier ones.
+#[derive(Debug, Clone)]
+pub struct Svg {
+    rows: usize,
+    cols: usize,
+    cell_size: usize,
+    elements: Vec<String>,
+}
+
+fn hex((r, g, b): Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+impl Svg {
+    pub fn new(rows: usize, cols: usize, cell_size: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            cell_size,
+            elements: Vec::new(),
+        }
+    }
+
+    fn center(&self, (row, col): Cell) -> (f64, f64) {
+        let size = self.cell_size as f64;
+        ((col as f64 + 0.5) * size, (row as f64 + 0.5) * size)
+    }
+
+    /// Fills every cell, such as walls or the plots of a region.
+    pub fn cells(
+        &mut self,
+        cells: impl IntoIterator<Item = Cell>,
+        fill: Rgb,
+        opacity: f64,
+    ) -> &mut Self {
+        let size = self.cell_size;
+        let rects: String = cells
+            .into_iter()
+            .map(|(row, col)| {
+                format!(
+                    r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#,
+                    col * size,
+                    row * size,
+                    size,
+                    size
+                )
+            })
+            .collect();
+
+        self.elements.push(format!(
+            r#"<g fill="{}" fill-opacity="{}">{}</g>"#,
+            hex(fill),
+            opacity,
+            rects
+        ));
+        self
+    }
+
+    /// A line through the centres of the cells, in order.
+    pub fn path(&mut self, cells: &[Cell], stroke: Rgb, width: f64, opacity: f64) -> &mut Self {
+        let points = cells
+            .iter()
+            .map(|&cell| {
+                let (x, y) = self.center(cell);
+                format!("{},{}", x, y)
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        self.elements.push(format!(
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-opacity="{}" stroke-linejoin="round" stroke-linecap="round"/>"#,
+            points,
+            hex(stroke),
+            width,
+            opacity
+        ));
+        self
+    }
+
+    /// Straight lines between pairs of cell corners, such as fence sides.
+    pub fn edges(
+        &mut self,
+        edges: impl IntoIterator<Item = (Cell, Cell)>,
+        stroke: Rgb,
+        width: f64,
+    ) -> &mut Self {
+        let size = self.cell_size;
+        let lines: String = edges
+            .into_iter()
+            .map(|((r0, c0), (r1, c1))| {
+                format!(
+                    r#"<line x1="{}" y1="{}" x2="{}" y2="{}"/>"#,
+                    c0 * size,
+                    r0 * size,
+                    c1 * size,
+                    r1 * size
+                )
+            })
+            .collect();
+
+        self.elements.push(format!(
+            r#"<g stroke="{}" stroke-width="{}" stroke-linecap="round">{}</g>"#,
+            hex(stroke),
+            width,
+            lines
+        ));
+        self
+    }
+
+    /// A dot in the middle of a cell, such as a start or an end.
+    pub fn marker(&mut self, cell: Cell, fill: Rgb) -> &mut Self {
+        let (x, y) = self.center(cell);
+        self.elements.push(format!(
+            r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+            x,
+            y,
+            self.cell_size as f64 * 0.35,
+            hex(fill)
+        ));
+        self
+    }
+}
+
+impl fmt::Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (width, height) = (self.cols * self.cell_size, self.rows * self.cell_size);
+
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            width, height
+        )?;
+        writeln!(
+            f,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            hex(BACKGROUND)
+        )?;
+        for element in &self.elements {
+            writeln!(f, "{}", element)?;
+        }
+        write!(f, "</svg>")
+    }
+}
+
+/// Solutions that can draw their answer.
+pub trait Draw {
+    fn svg(&self) -> Svg;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_layers() {
+        let mut svg = Svg::new(2, 3, 10);
+        svg.cells([(0, 1)], (255, 0, 0), 1.0)
+            .path(&[(0, 0), (1, 0), (1, 2)], (0, 255, 0), 2.0, 0.5)
+            .edges([((0, 0), (0, 3))], (0, 0, 0), 1.0)
+            .marker((1, 2), (0, 0, 255));
+
+        assert_eq!(
+            svg.to_string(),
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="30" height="20" viewBox="0 0 30 20">
+<rect width="100%" height="100%" fill="#0f0f23"/>
+<g fill="#ff0000" fill-opacity="1"><rect x="10" y="0" width="10" height="10"/></g>
+<polyline points="5,5 5,15 25,15" fill="none" stroke="#00ff00" stroke-width="2" stroke-opacity="0.5" stroke-linejoin="round" stroke-linecap="round"/>
+<g stroke="#000000" stroke-width="1" stroke-linecap="round"><line x1="0" y1="0" x2="30" y2="0"/></g>
+<circle cx="25" cy="15" r="3.5" fill="#0000ff"/>
+</svg>"##
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use std::io::{self, Read};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// The byte Ctrl-C sends once signals are off.
+pub const CTRL_C: u8 = 0x03;
+
+/// Puts the terminal in non-canonical mode so single key presses arrive
+/// without Enter, and restores the previous settings when dropped. Signals
+/// are off too, so Ctrl-C arrives as [`CTRL_C`] instead of killing the
+/// process before the terminal is restored.
+pub struct RawTerminal {
+    saved: Option<String>,
+}
+
+impl RawTerminal {
+    pub fn enable() -> Self {
+        let saved =
+            stty(&["-g"]).filter(|_| stty(&["-icanon", "-echo", "-isig", "min", "1"]).is_some());
+        Self { saved }
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        if let Some(saved) = &self.saved {
+            stty(&[saved.trim()]);
+        }
+    }
+}
+
+/// Runs `f` with echo, line editing and signals back on, so another program
+/// can use the terminal, then puts back the settings from before.
+pub fn with_cooked_terminal<T>(f: impl FnOnce() -> T) -> T {
+    let saved = stty(&["-g"]);
+    stty(&["icanon", "echo", "isig"]);
+    let result = f();
+    if let Some(saved) = &saved {
+        stty(&[saved.trim()]);
+    }
+
+    result
+}
+
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Reads stdin byte by byte on a background thread, for loops that must not
+/// block while waiting for a key.
+pub fn spawn_key_reader() -> Receiver<u8> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for byte in io::stdin().lock().bytes() {
+            if byte.ok().is_none_or(|byte| sender.send(byte).is_err()) {
+                break;
+            }
+        }
+    });
+
+    receiver
+}
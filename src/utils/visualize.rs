@@ -0,0 +1,162 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::terminal::{spawn_key_reader, RawTerminal, CTRL_C};
+
+pub type Rgb = (u8, u8, u8);
+
+/// Colour per tile character. Tiles that are not listed keep the terminal's
+/// default colour.
+pub type Palette = [(char, Rgb)];
+
+pub fn color(palette: &Palette, tile: char) -> Option<Rgb> {
+    palette
+        .iter()
+        .find(|&&(c, _)| c == tile)
+        .map(|&(_, rgb)| rgb)
+}
+
+/// One step of a grid simulation, one character per tile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub tiles: Vec<Vec<char>>,
+    pub caption: String,
+}
+
+impl Frame {
+    pub fn new(tiles: Vec<Vec<char>>, caption: impl Into<String>) -> Self {
+        Self {
+            tiles,
+            caption: caption.into(),
+        }
+    }
+
+    /// Reads a grid drawn one row per line, such as a `Display` dump.
+    pub fn parse(grid: &str, caption: impl Into<String>) -> Self {
+        Self::new(
+            grid.lines().map(|line| line.chars().collect()).collect(),
+            caption,
+        )
+    }
+
+    /// The frame with ANSI colour codes, only switching colour where
+    /// neighbouring tiles differ.
+    pub fn to_ansi(&self, palette: &Palette) -> String {
+        let mut output = String::new();
+
+        for row in &self.tiles {
+            let mut current = None;
+            for &tile in row {
+                let rgb = color(palette, tile);
+                if rgb != current {
+                    match rgb {
+                        Some((r, g, b)) => output += &format!("\x1b[38;2;{};{};{}m", r, g, b),
+                        None => output += "\x1b[39m",
+                    }
+                    current = rgb;
+                }
+                output.push(tile);
+            }
+            output += "\x1b[0m\n";
+        }
+        output += &self.caption;
+
+        output
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.tiles {
+            writeln!(f, "{}", row.iter().collect::<String>())?;
+        }
+        write!(f, "{}", self.caption)
+    }
+}
+
+/// Solutions that can show their simulation step by step.
+pub trait Visualize {
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame>>;
+    fn palette(&self) -> &'static Palette;
+}
+
+const CONTROLS: &str = "space pause, n step, +/- speed, q quit";
+
+/// Plays the frames in the terminal at `fps` frames per second. Space
+/// pauses, `n` steps one frame while paused, `+` and `-` change the speed
+/// and `q` or Ctrl-C stops.
+pub fn play(visualization: &impl Visualize, fps: f64) {
+    let _terminal = RawTerminal::enable();
+    let keys = spawn_key_reader();
+    let palette = visualization.palette();
+    let mut fps = fps.max(0.1);
+    let mut paused = false;
+    let mut stdout = io::stdout();
+
+    print!("\x1b[?25l\x1b[2J");
+
+    'frames: for (n, frame) in visualization.frames().enumerate() {
+        let deadline = Instant::now() + Duration::from_secs_f64(1.0 / fps);
+
+        loop {
+            let state = if paused { "paused" } else { "playing" };
+            print!(
+                "\x1b[H{}\x1b[K\nframe {} | {:.1} fps | {} | {}\x1b[K\x1b[J",
+                frame.to_ansi(palette),
+                n,
+                fps,
+                state,
+                CONTROLS
+            );
+            stdout.flush().unwrap();
+
+            let key = if paused {
+                keys.recv().ok()
+            } else {
+                match keys.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(key) => Some(key),
+                    Err(RecvTimeoutError::Timeout) => continue 'frames,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                        continue 'frames;
+                    }
+                }
+            };
+
+            match key {
+                // Without a keyboard there is no way to resume.
+                None => paused = false,
+                Some(b' ') => paused = !paused,
+                Some(b'n') if paused => continue 'frames,
+                Some(b'+') => fps *= 2.0,
+                Some(b'-') => fps = (fps / 2.0).max(0.1),
+                Some(b'q' | CTRL_C) => break 'frames,
+                Some(_) => {}
+            }
+        }
+    }
+
+    println!("\x1b[?25h");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_to_ansi() {
+        let frame = Frame::parse("#..\n.@#", "step 1");
+        let palette: &Palette = &[('#', (255, 0, 0)), ('@', (0, 0, 255))];
+
+        assert_eq!(frame.to_string(), "#..\n.@#\nstep 1");
+        assert_eq!(
+            frame.to_ansi(palette),
+            "\x1b[38;2;255;0;0m#\x1b[39m..\x1b[0m\n\
+             .\x1b[38;2;0;0;255m@\x1b[38;2;255;0;0m#\x1b[0m\n\
+             step 1"
+        );
+    }
+}
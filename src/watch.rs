@@ -0,0 +1,259 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::utils::{example_path, input_path};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// The day's source, the shared utils, the solver table in `main.rs` and the
+/// day's input and example.
+fn watched_paths(year: i32, day: i32) -> Vec<PathBuf> {
+    vec![
+        PathBuf::from(format!("src/y{}/day{:02}.rs", year, day)),
+        PathBuf::from("src/utils"),
+        PathBuf::from("src/main.rs"),
+        input_path(year, day),
+        example_path(year, day),
+    ]
+}
+
+/// Modification time of every file under `paths`, including files that are
+/// missing, so creating one counts as a change too.
+fn snapshot(paths: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut files = Vec::new();
+    for path in paths {
+        collect_files(path, &mut files);
+    }
+
+    files
+}
+
+fn collect_files(path: &Path, files: &mut Vec<(PathBuf, Option<SystemTime>)>) {
+    if path.is_dir() {
+        let mut entries: Vec<PathBuf> = fs::read_dir(path)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .collect();
+        entries.sort();
+
+        for entry in entries {
+            collect_files(&entry, files);
+        }
+    } else {
+        let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok();
+        files.push((path.to_path_buf(), modified));
+    }
+}
+
+struct Output {
+    success: bool,
+    text: String,
+}
+
+/// Runs a cargo subcommand with the same profile as the running binary.
+fn cargo(subcommand: &str, args: &[&str]) -> Output {
+    let mut command = Command::new("cargo");
+    command.arg(subcommand);
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+
+    capture(command.args(args))
+}
+
+fn capture(command: &mut Command) -> Output {
+    match command.output() {
+        Ok(output) => Output {
+            success: output.status.success(),
+            text: String::from_utf8_lossy(&output.stdout).into_owned()
+                + &String::from_utf8_lossy(&output.stderr),
+        },
+        Err(err) => Output {
+            success: false,
+            text: err.to_string(),
+        },
+    }
+}
+
+/// One line per compiler error, as printed with `--message-format short`.
+fn summarize_build(output: &str) -> String {
+    output
+        .lines()
+        .filter(|line| {
+            line.contains(": error") || line.starts_with("error[") || line.starts_with("error:")
+        })
+        .filter(|line| !line.starts_with("error: could not compile"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The result line of the test run, plus the failing tests and why they
+/// failed.
+fn summarize_tests(output: &str) -> String {
+    output
+        .lines()
+        .filter(|line| {
+            let line = line.trim();
+            line.starts_with("test result:")
+                || line.starts_with("---- ")
+                || line.contains("panicked at")
+                || line.starts_with("assertion")
+                || line.starts_with("left:")
+                || line.starts_with("right:")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The test summary, or the compiler errors if the tests did not build.
+fn summarize_test_run(tests: &Output) -> String {
+    if !tests.success && !tests.text.contains("test result:") {
+        return format!("Tests failed to build:\n{}", summarize_build(&tests.text));
+    }
+
+    format!("Tests:\n{}", summarize_tests(&tests.text))
+}
+
+fn draw(year: i32, day: i32, result: Option<&str>, status: &str) {
+    print!("\x1b[2J\x1b[H");
+    println!("Watching year {} day {}, Ctrl+C to stop\n", year, day);
+    if let Some(result) = result {
+        println!("{}\n", result.trim_end());
+    }
+    print!("{}", status);
+    io::stdout().flush().unwrap();
+}
+
+/// Rebuilds, then reruns the day's tests and solution. Returns the new
+/// result, or `None` if the build failed.
+fn rebuild(year: i32, day: i32, previous: Option<&str>) -> Option<String> {
+    draw(year, day, previous, "Building...");
+
+    let build = cargo("build", &["--bin", "aoc", "--message-format", "short"]);
+    if !build.success {
+        let status = format!("Build failed:\n{}", summarize_build(&build.text));
+        draw(year, day, previous, &status);
+        return None;
+    }
+
+    draw(year, day, previous, "Testing...");
+    let filter = format!("y{}::day{:02}::", year, day);
+    let tests = cargo(
+        "test",
+        &["-q", "--bin", "aoc", "--message-format", "short", &filter],
+    );
+
+    // The running binary may have just been replaced, so the new one is
+    // started through cargo.
+    let (year_arg, day_arg) = (year.to_string(), day.to_string());
+    let solution = cargo(
+        "run",
+        &[
+            "-q", "--bin", "aoc", "--", "--year", &year_arg, "--day", &day_arg,
+        ],
+    );
+
+    let result = format!(
+        "{}\n\nSolution:\n{}",
+        summarize_test_run(&tests),
+        solution.text.trim_end()
+    );
+    draw(year, day, Some(&result), "");
+
+    Some(result)
+}
+
+/// Watches the day and reruns it after every change. The last good result
+/// stays on screen while a rebuild is failing.
+pub fn run(year: i32, day: i32) {
+    let paths = watched_paths(year, day);
+    let mut seen = None;
+    let mut result: Option<String> = None;
+
+    loop {
+        let current = snapshot(&paths);
+        if seen.as_ref() != Some(&current) {
+            seen = Some(current);
+            if let Some(new) = rebuild(year, day, result.as_deref()) {
+                result = Some(new);
+            }
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summaries_are_short() {
+        let build = "   Compiling aoc v0.1.0\n\
+                     src/y2024/day06.rs:12:5: error[E0425]: cannot find value `x` in this scope\n\
+                     src/y2024/day06.rs:3:5: warning: unused import: `std::fmt`\n\
+                     src/y2024/day06.rs:8:9: warning: unused variable: `error`\n\
+                     error: could not compile `aoc` (bin \"aoc\") due to 1 previous error";
+        assert_eq!(
+            summarize_build(build),
+            "src/y2024/day06.rs:12:5: error[E0425]: cannot find value `x` in this scope"
+        );
+
+        let tests = "\nrunning 3 tests\n..F\nfailures:\n\n\
+                     ---- y2024::day06::tests::part_two stdout ----\n\n\
+                     thread 'y2024::day06::tests::part_two' panicked at src/y2024/day06.rs:9:9:\n\
+                     assertion `left == right` failed\n  left: \"5\"\n right: \"6\"\n\
+                     note: run with `RUST_BACKTRACE=1`\n\n\
+                     test result: FAILED. 2 passed; 1 failed; 0 ignored";
+        assert_eq!(summarize_tests(tests).lines().count(), 6);
+        assert!(
+            summarize_tests(tests).ends_with("test result: FAILED. 2 passed; 1 failed; 0 ignored")
+        );
+    }
+
+    #[test]
+    fn test_build_failures_are_shown() {
+        let tests = Output {
+            success: false,
+            text: "src/y2024/day06.rs:40:9: error[E0308]: mismatched types\n\
+                   error: could not compile `aoc` (bin \"aoc\" test) due to 1 previous error"
+                .to_string(),
+        };
+        assert_eq!(
+            summarize_test_run(&tests),
+            "Tests failed to build:\nsrc/y2024/day06.rs:40:9: error[E0308]: mismatched types"
+        );
+
+        let tests = Output {
+            success: false,
+            text: "test result: FAILED. 2 passed; 1 failed; 0 ignored".to_string(),
+        };
+        assert_eq!(
+            summarize_test_run(&tests),
+            "Tests:\ntest result: FAILED. 2 passed; 1 failed; 0 ignored"
+        );
+    }
+
+    #[test]
+    fn snapshot_includes_missing_files() {
+        let paths = watched_paths(1999, 1);
+        let files = snapshot(&paths);
+
+        assert!(files.contains(&(PathBuf::from("src/y1999/day01.rs"), None)));
+        assert!(files
+            .iter()
+            .any(|(path, modified)| { path.starts_with("src/utils") && modified.is_some() }));
+        assert!(files.contains(&(
+            PathBuf::from("src/main.rs"),
+            fs::metadata("src/main.rs")
+                .and_then(|meta| meta.modified())
+                .ok()
+        )));
+    }
+}
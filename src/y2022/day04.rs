@@ -1,22 +1,20 @@
+use crate::utils::interval::Interval;
 use crate::utils::AdventDay;
 
-struct Elf {
-    min: i32,
-    max: i32,
+/// The section assignments of each pair of elves.
+fn parse_pairs(input: &str) -> Vec<(Interval<i32>, Interval<i32>)> {
+    input
+        .lines()
+        .map(|line| {
+            let (a, b) = line.split_once(',').unwrap();
+            (parse_sections(a), parse_sections(b))
+        })
+        .collect()
 }
 
-impl Elf {
-    fn new(min: i32, max: i32) -> Self {
-        Self { min, max }
-    }
-
-    fn contains(&self, other: &Self) -> bool {
-        self.min <= other.min && other.max <= self.max
-    }
-
-    fn overlaps(&self, other: &Self) -> bool {
-        self.min <= other.min && other.min <= self.max
-    }
+fn parse_sections(range: &str) -> Interval<i32> {
+    let (first, last) = range.split_once('-').unwrap();
+    Interval::inclusive(first.parse().unwrap(), last.parse().unwrap())
 }
 
 pub struct Day04 {
@@ -29,49 +27,17 @@ impl AdventDay for Day04 {
     }
 
     fn part_one(&self) -> String {
-        self.input
-            .lines()
-            .map(|line| {
-                let (a, b) = {
-                    let mut parts = line.split(',').map(|pair| {
-                        let mut nums = pair.split('-');
-                        let min = nums.next().unwrap().parse::<i32>().unwrap();
-                        let max = nums.next().unwrap().parse::<i32>().unwrap();
-
-                        Elf::new(min, max)
-                    });
-
-                    (parts.next().unwrap(), parts.next().unwrap())
-                };
-
-                a.contains(&b) || b.contains(&a)
-            })
-            // Count all the true values
-            .filter(|&x| x)
+        parse_pairs(&self.input)
+            .iter()
+            .filter(|(a, b)| a.contains_interval(b) || b.contains_interval(a))
             .count()
             .to_string()
     }
 
     fn part_two(&self) -> String {
-        self.input
-            .lines()
-            .map(|line| {
-                let (a, b) = {
-                    let mut parts = line.split(',').map(|pair| {
-                        let mut nums = pair.split('-');
-                        let min = nums.next().unwrap().parse::<i32>().unwrap();
-                        let max = nums.next().unwrap().parse::<i32>().unwrap();
-
-                        Elf::new(min, max)
-                    });
-
-                    (parts.next().unwrap(), parts.next().unwrap())
-                };
-
-                a.overlaps(&b) || b.overlaps(&a)
-            })
-            // Count all the true values
-            .filter(|&x| x)
+        parse_pairs(&self.input)
+            .iter()
+            .filter(|(a, b)| a.overlaps(b))
             .count()
             .to_string()
     }
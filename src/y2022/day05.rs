@@ -1,13 +1,111 @@
 use crate::utils::AdventDay;
 use itertools::Itertools;
 
-type Game = Vec<Vec<char>>;
-type Moves = Vec<(i32, i32, i32)>;
+/// Crates from bottom to top, so the top crate is at the end.
+type Stack = Vec<char>;
+type Game = Vec<Stack>;
+type Moves = Vec<Move>;
+
+/// Moves `count` crates between two stacks, numbered from 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// How a crane carries crates from one stack to another.
+pub trait Crane {
+    fn apply(&self, stacks: &mut [Stack], mv: Move);
+}
+
+/// Lifts one crate at a time, which reverses their order.
+pub struct CrateMover9000;
+
+/// Lifts all crates at once, keeping their order.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9000 {
+    fn apply(&self, stacks: &mut [Stack], mv: Move) {
+        for _ in 0..mv.count {
+            let c = stacks[mv.from].pop().unwrap();
+            stacks[mv.to].push(c);
+        }
+    }
+}
+
+impl Crane for CrateMover9001 {
+    fn apply(&self, stacks: &mut [Stack], mv: Move) {
+        let from = &mut stacks[mv.from];
+        let lifted = from.split_off(from.len() - mv.count);
+        stacks[mv.to].extend(lifted);
+    }
+}
+
+/// Runs every move, calling `on_move` with the stacks after each one.
+fn simulate(
+    game: &mut Game,
+    moves: &[Move],
+    crane: &dyn Crane,
+    mut on_move: impl FnMut(Move, &Game),
+) {
+    for &mv in moves {
+        crane.apply(game, mv);
+        on_move(mv, game);
+    }
+}
+
+fn top_crates(game: &Game) -> String {
+    game.iter().filter_map(|stack| stack.last()).join("")
+}
+
+/// Draws the stacks the way the puzzle does, with the stack numbers below.
+pub fn render(game: &Game) -> String {
+    let width = game.len().to_string().len().max(3);
+    let height = game.iter().map(Vec::len).max().unwrap_or(0);
+    let mut lines = Vec::with_capacity(height + 1);
+
+    for level in (0..height).rev() {
+        let line = game
+            .iter()
+            .map(|stack| match stack.get(level) {
+                Some(c) => format!("{:^width$}", format!("[{}]", c)),
+                None => " ".repeat(width),
+            })
+            .join(" ");
+        lines.push(line.trim_end().to_string());
+    }
+
+    let labels = (1..=game.len()).map(|n| format!("{:^width$}", n)).join(" ");
+    lines.push(labels.trim_end().to_string());
+
+    lines.join("\n")
+}
 
 pub struct Day05 {
     input: String,
 }
 
+impl Day05 {
+    /// The stacks after every move, starting from the initial drawing.
+    pub fn trace(&self, crane: &dyn Crane) -> String {
+        let (mut game, moves) = parse_data(&self.input);
+        let mut frames = vec![render(&game)];
+
+        simulate(&mut game, &moves, crane, |mv, game| {
+            frames.push(format!(
+                "move {} from {} to {}\n{}",
+                mv.count,
+                mv.from + 1,
+                mv.to + 1,
+                render(game)
+            ));
+        });
+
+        frames.join("\n\n")
+    }
+}
+
 impl AdventDay for Day05 {
     fn new(input: String) -> Self {
         Self { input }
@@ -15,30 +113,14 @@ impl AdventDay for Day05 {
 
     fn part_one(&self) -> String {
         let (mut game, moves) = parse_data(&self.input);
-
-        for (count, from, to) in moves {
-            for _ in 0..count {
-                let c = game[from as usize - 1][0];
-                game[to as usize - 1].insert(0, c);
-                game[from as usize - 1].remove(0);
-            }
-        }
-
-        game.iter().map(|stack| stack.first().unwrap()).join("")
+        simulate(&mut game, &moves, &CrateMover9000, |_, _| {});
+        top_crates(&game)
     }
 
     fn part_two(&self) -> String {
         let (mut game, moves) = parse_data(&self.input);
-
-        for (count, from, to) in moves {
-            for i in 0..count {
-                let c = game[from as usize - 1][0];
-                game[to as usize - 1].insert(i as usize, c);
-                game[from as usize - 1].remove(0);
-            }
-        }
-
-        game.iter().map(|stack| stack.first().unwrap()).join("")
+        simulate(&mut game, &moves, &CrateMover9001, |_, _| {});
+        top_crates(&game)
     }
 }
 
@@ -48,27 +130,32 @@ fn parse_data(input: &str) -> (Game, Moves) {
     let moves = parse_moves(moves);
     (game, moves)
 }
+
+/// Reads the drawing bottom up. Each crate goes to the stack whose number
+/// is closest to it, so wide stack numbers and lines missing their trailing
+/// spaces are both fine.
 fn parse_game(game: &str) -> Game {
     let lines: Vec<&str> = game.lines().collect();
-    let last_line = lines.last().unwrap();
-    let num_stacks = last_line.split_whitespace().count();
-
-    let mut stacks: Game = vec![Vec::new(); num_stacks];
+    let (label_line, crate_lines) = lines.split_last().unwrap();
+
+    let centers: Vec<usize> = label_line
+        .char_indices()
+        .filter(|&(i, c)| !c.is_whitespace() && (i == 0 || label_line[..i].ends_with(' ')))
+        .map(|(i, _)| {
+            let len = label_line[i..].split_whitespace().next().unwrap().len();
+            i + len / 2
+        })
+        .collect();
 
-    for &line in &lines[..lines.len() - 1] {
-        if !line.contains('[') {
-            continue;
-        }
+    let mut stacks: Game = vec![Vec::new(); centers.len()];
 
-        for (i, stack) in stacks.iter_mut().enumerate().take(num_stacks) {
-            let start = i * 4;
-            if start + 3 <= line.len() {
-                let segment = &line[start..start + 3];
-                if segment.starts_with('[') && segment.ends_with(']') && segment.len() == 3 {
-                    let c = segment.chars().nth(1).unwrap();
-                    stack.push(c);
-                }
-            }
+    for line in crate_lines.iter().rev() {
+        for (i, _) in line.match_indices('[') {
+            let c = line[i + 1..].chars().next().unwrap();
+            let stack = (0..centers.len())
+                .min_by_key(|&s| centers[s].abs_diff(i + 1))
+                .unwrap();
+            stacks[stack].push(c);
         }
     }
 
@@ -83,9 +170,13 @@ fn parse_moves(moves: &str) -> Moves {
         .map(|line| {
             let parts = line.split(" ").collect::<Vec<&str>>();
             let count = parts.get(1).unwrap().parse().unwrap();
-            let from = parts.get(3).unwrap().parse().unwrap();
-            let to = parts.get(5).unwrap().parse().unwrap();
-            (count, from, to)
+            let from: usize = parts.get(3).unwrap().parse().unwrap();
+            let to: usize = parts.get(5).unwrap().parse().unwrap();
+            Move {
+                count,
+                from: from - 1,
+                to: to - 1,
+            }
         })
         .collect()
 }
@@ -115,4 +206,29 @@ move 1 from 1 to 2"#;
         let day05 = Day05::new(DATA.to_string());
         assert_eq!(day05.part_two(), "MCD");
     }
+
+    #[test]
+    fn trace_renders_each_move() {
+        let day05 = Day05::new(DATA.to_string());
+        let trace = day05.trace(&CrateMover9001);
+        let frames: Vec<&str> = trace.split("\n\n").collect();
+
+        assert_eq!(frames.len(), 5);
+        assert_eq!(frames[0], DATA.split_once("\n\n").unwrap().0);
+        assert_eq!(
+            frames[2],
+            "move 3 from 1 to 3\n        [D]\n        [N]\n    [C] [Z]\n    [M] [P]\n 1   2   3"
+        );
+    }
+
+    #[test]
+    fn parse_game_with_wide_numbers() {
+        let drawing = (1..=11)
+            .map(|n| vec![char::from(b'A' + n as u8); n % 3])
+            .collect::<Game>();
+        let rendered = render(&drawing);
+
+        assert!(rendered.ends_with(" 9  10  11"));
+        assert_eq!(parse_game(&rendered), drawing);
+    }
 }
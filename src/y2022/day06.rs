@@ -1,5 +1,47 @@
 use crate::utils::AdventDay;
-use std::collections::HashSet;
+
+const PACKET_MARKER: usize = 4;
+const MESSAGE_MARKER: usize = 14;
+
+/// Position just after the first `size` bytes that are all different.
+/// The window slides one byte at a time, keeping a count per byte value and
+/// the number of values seen more than once.
+pub fn find_marker(data: &[u8], size: usize) -> Option<usize> {
+    if size == 0 {
+        return Some(0);
+    }
+
+    let mut counts = [0usize; 256];
+    let mut repeated = 0;
+
+    for (i, &byte) in data.iter().enumerate() {
+        counts[byte as usize] += 1;
+        if counts[byte as usize] == 2 {
+            repeated += 1;
+        }
+
+        if i >= size {
+            let old = data[i - size] as usize;
+            counts[old] -= 1;
+            if counts[old] == 1 {
+                repeated -= 1;
+            }
+        }
+
+        if i + 1 >= size && repeated == 0 {
+            return Some(i + 1);
+        }
+    }
+
+    None
+}
+
+fn describe_marker(data: &str, size: usize) -> String {
+    match find_marker(data.trim().as_bytes(), size) {
+        Some(start) => start.to_string(),
+        None => "no marker".to_string(),
+    }
+}
 
 pub struct Day06 {
     input: String,
@@ -11,43 +53,11 @@ impl AdventDay for Day06 {
     }
 
     fn part_one(&self) -> String {
-        let mut start = 0;
-        let size = 4;
-
-        for i in 0..self.input.len() - 1 {
-            let chars = self
-                .input
-                .chars()
-                .skip(i)
-                .take(size)
-                .collect::<HashSet<_>>();
-            if chars.len() == size {
-                start = i + size;
-                break;
-            }
-        }
-
-        start.to_string()
+        describe_marker(&self.input, PACKET_MARKER)
     }
 
     fn part_two(&self) -> String {
-        let mut start = 0;
-        let size = 14;
-
-        for i in 0..self.input.len() - 1 {
-            let chars = self
-                .input
-                .chars()
-                .skip(i)
-                .take(size)
-                .collect::<HashSet<_>>();
-            if chars.len() == size {
-                start = i + size;
-                break;
-            }
-        }
-
-        start.to_string()
+        describe_marker(&self.input, MESSAGE_MARKER)
     }
 }
 
@@ -78,4 +88,15 @@ mod tests {
         let day06 = Day06::new("mjqjpqmgbljsphdztnvjfqwrcgsmlb".to_string());
         assert_eq!(day06.part_two(), "19");
     }
+
+    #[test]
+    fn find_marker_windows() {
+        assert_eq!(find_marker(b"abcabc", 3), Some(3));
+        assert_eq!(find_marker(b"aabbcc", 2), Some(3));
+        assert_eq!(find_marker(b"aaaa", 2), None);
+        assert_eq!(find_marker(b"abc", 4), None);
+        assert_eq!(find_marker(&[0, 255, 0, 128, 7], 3), Some(4));
+        assert_eq!(find_marker(b"", 0), Some(0));
+        assert_eq!(find_marker(b"abc", 0), Some(0));
+    }
 }
@@ -1,7 +1,167 @@
-use itertools::Itertools;
+use std::collections::{HashMap, VecDeque};
 
 use crate::utils::AdventDay;
 
+/// Words and the digit they stand for.
+pub type Vocabulary<'a> = [(&'a str, u32)];
+
+pub const DIGITS: &Vocabulary = &[
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+pub const SPELLED: &Vocabulary = &[
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+/// Aho-Corasick automaton that finds every word of a vocabulary in a single
+/// pass, including words that overlap like "eightwo".
+struct Automaton {
+    goto: Vec<HashMap<u8, usize>>,
+    fail: Vec<usize>,
+    /// Words ending at each node, as (length, value)
+    outputs: Vec<Vec<(usize, u32)>>,
+    longest: usize,
+}
+
+impl Automaton {
+    fn new(words: &[(Vec<u8>, u32)]) -> Self {
+        let mut goto = vec![HashMap::new()];
+        let mut outputs = vec![Vec::new()];
+
+        for (word, value) in words {
+            let mut node = 0;
+            for &byte in word {
+                node = match goto[node].get(&byte) {
+                    Some(&next) => next,
+                    None => {
+                        goto.push(HashMap::new());
+                        outputs.push(Vec::new());
+                        let next = goto.len() - 1;
+                        goto[node].insert(byte, next);
+                        next
+                    }
+                };
+            }
+            outputs[node].push((word.len(), *value));
+        }
+
+        // Breadth first, so the fail link of a node's parent is always known.
+        let mut fail = vec![0; goto.len()];
+        let mut queue: VecDeque<usize> = goto[0].values().copied().collect();
+
+        while let Some(node) = queue.pop_front() {
+            for (&byte, &child) in &goto[node] {
+                let mut link = fail[node];
+                while link != 0 && !goto[link].contains_key(&byte) {
+                    link = fail[link];
+                }
+                fail[child] = goto[link].get(&byte).copied().unwrap_or(0);
+
+                let inherited = outputs[fail[child]].clone();
+                outputs[child].extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        Self {
+            goto,
+            fail,
+            outputs,
+            longest: words.iter().map(|(word, _)| word.len()).max().unwrap_or(0),
+        }
+    }
+
+    /// The value of the word that starts first in `bytes`. Scanning stops as
+    /// soon as no word starting earlier can still end.
+    fn first_match(&self, bytes: impl Iterator<Item = u8>) -> Option<u32> {
+        let mut node = 0;
+        let mut best: Option<(usize, u32)> = None;
+
+        for (i, byte) in bytes.enumerate() {
+            if best.is_some_and(|(start, _)| i >= start + self.longest) {
+                break;
+            }
+
+            while node != 0 && !self.goto[node].contains_key(&byte) {
+                node = self.fail[node];
+            }
+            node = self.goto[node].get(&byte).copied().unwrap_or(0);
+
+            for &(len, value) in &self.outputs[node] {
+                let start = i + 1 - len;
+                if best.is_none_or(|(best_start, _)| start < best_start) {
+                    best = Some((start, value));
+                }
+            }
+        }
+
+        best.map(|(_, value)| value)
+    }
+}
+
+/// Finds the first and last digit of a line. The last digit is found by
+/// running a second automaton over the reversed words from the end of the
+/// line, so neither search reads more of the line than it needs.
+pub struct DigitMatcher {
+    forward: Automaton,
+    backward: Automaton,
+}
+
+impl DigitMatcher {
+    pub fn new(vocabulary: &Vocabulary) -> Self {
+        let forward: Vec<(Vec<u8>, u32)> = vocabulary
+            .iter()
+            .map(|(word, value)| (word.as_bytes().to_vec(), *value))
+            .collect();
+        let backward: Vec<(Vec<u8>, u32)> = forward
+            .iter()
+            .map(|(word, value)| (word.iter().rev().copied().collect(), *value))
+            .collect();
+
+        Self {
+            forward: Automaton::new(&forward),
+            backward: Automaton::new(&backward),
+        }
+    }
+
+    pub fn first(&self, line: &str) -> Option<u32> {
+        self.forward.first_match(line.bytes())
+    }
+
+    pub fn last(&self, line: &str) -> Option<u32> {
+        self.backward.first_match(line.bytes().rev())
+    }
+
+    pub fn calibration_value(&self, line: &str) -> Option<u32> {
+        Some(self.first(line)? * 10 + self.last(line)?)
+    }
+}
+
+fn sum_calibration_values(input: &str, vocabulary: &Vocabulary) -> u32 {
+    let matcher = DigitMatcher::new(vocabulary);
+
+    input
+        .lines()
+        .map(|line| matcher.calibration_value(line).unwrap())
+        .sum()
+}
+
 pub struct Day01 {
     input: String,
 }
@@ -12,47 +172,12 @@ impl AdventDay for Day01 {
     }
 
     fn part_one(&self) -> String {
-        self.input
-            .lines()
-            .map(|line| {
-                let nums = line.chars().filter(|c| c.is_ascii_digit()).collect_vec();
-                let first = nums.first().unwrap().to_digit(10).unwrap();
-                let last = nums.last().unwrap().to_digit(10).unwrap();
-                format!("{}{}", first, last).parse::<i32>().unwrap()
-            })
-            .sum::<i32>()
-            .to_string()
+        sum_calibration_values(&self.input, DIGITS).to_string()
     }
 
     fn part_two(&self) -> String {
-        let numbers = [
-            ("one", "one1one"),
-            ("two", "two2two"),
-            ("three", "three3three"),
-            ("four", "four4four"),
-            ("five", "five5five"),
-            ("six", "six6six"),
-            ("seven", "seven7seven"),
-            ("eight", "eight8eight"),
-            ("nine", "nine9nine"),
-        ];
-
-        self.input
-            .lines()
-            .map(|line| {
-                let new_line = numbers
-                    .iter()
-                    .fold(line.to_string(), |acc, (word, num)| acc.replace(word, num));
-                let nums = new_line
-                    .chars()
-                    .filter(|c| c.is_ascii_digit())
-                    .collect_vec();
-                let first = nums.first().unwrap().to_digit(10).unwrap();
-                let last = nums.last().unwrap().to_digit(10).unwrap();
-                format!("{}{}", first, last).parse::<i32>().unwrap()
-            })
-            .sum::<i32>()
-            .to_string()
+        let vocabulary = [DIGITS, SPELLED].concat();
+        sum_calibration_values(&self.input, &vocabulary).to_string()
     }
 }
 
@@ -84,4 +209,24 @@ zoneight234
         let day01 = Day01::new(DATA_2.to_string());
         assert_eq!(day01.part_two(), "281");
     }
+
+    #[test]
+    fn overlapping_words() {
+        let vocabulary = [DIGITS, SPELLED].concat();
+        let matcher = DigitMatcher::new(&vocabulary);
+
+        assert_eq!(matcher.calibration_value("eightwo"), Some(82));
+        assert_eq!(matcher.calibration_value("oneight"), Some(18));
+        assert_eq!(matcher.calibration_value("xtwone3four"), Some(24));
+        assert_eq!(matcher.calibration_value("nothing"), None);
+    }
+
+    #[test]
+    fn custom_vocabulary() {
+        let matcher = DigitMatcher::new(&[("zero", 0), ("un", 1), ("deux", 2), ("xdeuxy", 7)]);
+
+        assert_eq!(matcher.calibration_value("zerounx"), Some(1));
+        assert_eq!(matcher.first("axdeuxyun"), Some(7));
+        assert_eq!(matcher.last("unzeroab"), Some(0));
+    }
 }
@@ -1,57 +1,53 @@
+use std::collections::HashMap;
+
 use itertools::Itertools;
 
 use crate::utils::AdventDay;
 
+/// Number of cubes per colour name.
+pub type CubeSet<'a> = HashMap<&'a str, u32>;
+
+/// The bag the elf asks about in part one.
+pub const DEFAULT_BAG: &[(&str, u32)] = &[("red", 12), ("green", 13), ("blue", 14)];
+
 #[derive(Debug)]
-struct Game {
+struct Game<'a> {
     id: u32,
-    sets: Vec<Vec<Color>>,
+    sets: Vec<CubeSet<'a>>,
 }
 
-impl Game {
-    fn new(id: u32, sets: Vec<Vec<Color>>) -> Self {
+impl<'a> Game<'a> {
+    fn new(id: u32, sets: Vec<CubeSet<'a>>) -> Self {
         Self { id, sets }
     }
 
-    fn is_valid(&self) -> bool {
-        self.sets
-            .iter()
-            .all(|set| set.iter().all(|color| color.is_valid()))
+    /// Whether every set could have been drawn from `bag`. Colours missing
+    /// from the bag have no cubes.
+    fn is_possible(&self, bag: &CubeSet) -> bool {
+        self.sets.iter().all(|set| {
+            set.iter()
+                .all(|(color, count)| count <= bag.get(color).unwrap_or(&0))
+        })
     }
-}
 
-#[derive(Debug)]
-enum Color {
-    Green(u32),
-    Red(u32),
-    Blue(u32),
-}
-
-impl Color {
-    fn is_valid(&self) -> bool {
-        match self {
-            Color::Red(count) => *count <= 12,
-            Color::Green(count) => *count <= 13,
-            Color::Blue(count) => *count <= 14,
+    /// The fewest cubes of each colour seen that make the game possible.
+    fn minimum_bag(&self) -> CubeSet<'a> {
+        let mut bag = CubeSet::new();
+        for (&color, &count) in self.sets.iter().flatten() {
+            let min = bag.entry(color).or_default();
+            *min = (*min).max(count);
         }
+
+        bag
     }
 }
 
-fn power_of_set(set: &[Vec<Color>]) -> u32 {
-    let (red, green, blue) = set.iter().fold((0, 0, 0), |acc, colors| {
-        colors
-            .iter()
-            .fold(acc, |(red, green, blue), color| match color {
-                Color::Red(count) => (red.max(*count), green, blue),
-                Color::Green(count) => (red, green.max(*count), blue),
-                Color::Blue(count) => (red, green, blue.max(*count)),
-            })
-    });
-
-    red * green * blue
+/// Product of the cube counts of every colour in the bag.
+fn power_of_set(bag: &CubeSet) -> u32 {
+    bag.values().product()
 }
 
-fn parse_input(input: &str) -> Vec<Game> {
+fn parse_input(input: &str) -> Vec<Game<'_>> {
     input
         .lines()
         .map(|game| {
@@ -75,13 +71,7 @@ fn parse_input(input: &str) -> Vec<Game> {
                         .map(|color| {
                             let (count_str, color_str) =
                                 color.split_whitespace().collect_tuple().unwrap();
-                            let count = count_str.parse::<u32>().unwrap();
-                            match color_str {
-                                "red" => Color::Red(count),
-                                "green" => Color::Green(count),
-                                "blue" => Color::Blue(count),
-                                _ => panic!("Invalid color"),
-                            }
+                            (color_str, count_str.parse::<u32>().unwrap())
                         })
                         .collect()
                 })
@@ -96,26 +86,43 @@ pub struct Day02 {
     input: String,
 }
 
+impl Day02 {
+    /// Ids of the games that could have been played with `bag`.
+    pub fn possible_games(&self, bag: &[(&str, u32)]) -> Vec<u32> {
+        let bag: CubeSet = bag.iter().copied().collect();
+
+        parse_input(&self.input)
+            .iter()
+            .filter(|game| game.is_possible(&bag))
+            .map(|game| game.id)
+            .collect()
+    }
+
+    /// The smallest bag for each game, as (id, bag).
+    pub fn minimum_bags(&self) -> Vec<(u32, CubeSet<'_>)> {
+        parse_input(&self.input)
+            .iter()
+            .map(|game| (game.id, game.minimum_bag()))
+            .collect()
+    }
+}
+
 impl AdventDay for Day02 {
     fn new(input: String) -> Self {
         Self { input }
     }
 
     fn part_one(&self) -> String {
-        let games = parse_input(&self.input);
-        games
+        self.possible_games(DEFAULT_BAG)
             .iter()
-            .filter(|game| game.is_valid())
-            .map(|game| game.id)
             .sum::<u32>()
             .to_string()
     }
 
     fn part_two(&self) -> String {
-        let games = parse_input(&self.input);
-        games
+        self.minimum_bags()
             .iter()
-            .map(|game| power_of_set(&game.sets))
+            .map(|(_, bag)| power_of_set(bag))
             .sum::<u32>()
             .to_string()
     }
@@ -142,4 +149,37 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
         let day02 = Day02::new(DATA.to_string());
         assert_eq!(day02.part_two(), "2286");
     }
+
+    #[test]
+    fn what_if_bags() {
+        let day02 = Day02::new(DATA.to_string());
+
+        assert_eq!(
+            day02.possible_games(&[("red", 20), ("green", 13), ("blue", 15)]),
+            [1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            day02.possible_games(&[("red", 6), ("green", 3), ("blue", 6)]),
+            [1, 2, 5]
+        );
+        assert!(day02
+            .possible_games(&[("red", 20), ("green", 13)])
+            .is_empty());
+    }
+
+    #[test]
+    fn unknown_colours() {
+        let day02 = Day02::new("Game 7: 2 teal, 1 red; 5 teal".to_string());
+
+        assert_eq!(day02.possible_games(DEFAULT_BAG), []);
+        assert_eq!(day02.possible_games(&[("teal", 5), ("red", 1)]), [7]);
+        assert_eq!(
+            day02.minimum_bags()[0].1,
+            CubeSet::from([("teal", 5), ("red", 1)])
+        );
+        assert_eq!(day02.part_two(), "5");
+
+        let day02 = Day02::new("Game 8: 3 red, 2 teal; 4 red\nGame 9: 2 green, 3 blue".to_string());
+        assert_eq!(day02.part_two(), (4 * 2 + 2 * 3).to_string());
+    }
 }
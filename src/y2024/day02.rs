@@ -1,47 +1,117 @@
-use std::cmp::Ordering;
-
-use itertools::Itertools;
-
 use crate::utils::AdventDay;
 
 pub struct Day02 {
     input: String,
 }
 
-fn is_safe_rate_of_change(vec: Vec<i32>) -> bool {
-    let first = vec.first().expect("Expected element on index 0");
-    let second = vec.get(1).expect("Expected element on index 1");
-    let ordering = first.cmp(second);
+/// What makes a report safe: every step between kept levels moves in the
+/// same direction by `min_step..=max_step`, after removing at most
+/// `max_removals` levels.
+#[derive(Debug, Clone, Copy)]
+pub struct Tolerance {
+    pub min_step: i32,
+    pub max_step: i32,
+    pub max_removals: usize,
+}
 
-    if ordering == Ordering::Equal {
-        return false;
+const STRICT: Tolerance = Tolerance {
+    min_step: 1,
+    max_step: 3,
+    max_removals: 0,
+};
+
+const DAMPENED: Tolerance = Tolerance {
+    max_removals: 1,
+    ..STRICT
+};
+
+/// Finds the fewest levels to remove so the report is safe, or `None` if
+/// that takes more than `tolerance.max_removals` levels. Indices are returned
+/// in increasing order.
+pub fn levels_to_remove(report: &[i32], tolerance: &Tolerance) -> Option<Vec<usize>> {
+    let increasing = removals_in_direction(report, 1, tolerance);
+    let decreasing = removals_in_direction(report, -1, tolerance);
+
+    match (increasing, decreasing) {
+        (Some(a), Some(b)) if b.len() < a.len() => Some(b),
+        (Some(a), _) => Some(a),
+        (None, b) => b,
     }
+}
 
-    for (&i, &j) in vec.iter().tuple_windows() {
-        if i.cmp(&j) != ordering {
-            return false;
+// `removed[i]` is the fewest removals for a safe report ending at level `i`
+// with `i` kept. Skipping more than `max_removals` levels in a row is never
+// allowed, so only that many predecessors are looked at and the whole pass
+// is linear for a fixed tolerance.
+fn removals_in_direction(report: &[i32], sign: i32, tolerance: &Tolerance) -> Option<Vec<usize>> {
+    let k = tolerance.max_removals;
+    let len = report.len();
+    let fits = |from: i32, to: i32| {
+        let step = (to - from) * sign;
+        tolerance.min_step <= step && step <= tolerance.max_step
+    };
+
+    let mut removed = vec![usize::MAX; len];
+    let mut previous: Vec<Option<usize>> = vec![None; len];
+
+    for i in 0..len {
+        // Drop every level before `i`.
+        if i <= k {
+            removed[i] = i;
         }
 
-        if (i - j).abs() > 3 {
-            return false;
+        for j in i.saturating_sub(k + 1)..i {
+            if removed[j] == usize::MAX || !fits(report[j], report[i]) {
+                continue;
+            }
+
+            let cost = removed[j] + (i - j - 1);
+            if cost <= k && cost < removed[i] {
+                removed[i] = cost;
+                previous[i] = Some(j);
+            }
         }
     }
 
-    true
-}
+    let (last, total) = (0..len)
+        .filter(|&i| removed[i] != usize::MAX)
+        .map(|i| (i, removed[i] + (len - 1 - i)))
+        .min_by_key(|&(_, total)| total)
+        .unwrap_or((0, 0));
+    if total > k {
+        return None;
+    }
 
-fn vec_combinations(vec: Vec<i32>) -> Vec<Vec<i32>> {
-    let mut combinations = Vec::new();
+    let mut kept = vec![false; len];
+    let mut current = (len > 0).then_some(last);
+    while let Some(i) = current {
+        kept[i] = true;
+        current = previous[i];
+    }
 
-    combinations.push(vec.clone());
+    Some((0..len).filter(|&i| !kept[i]).collect())
+}
 
-    for i in 0..vec.len() {
-        let mut new_vec = vec.clone();
-        new_vec.remove(i);
-        combinations.push(new_vec);
-    }
+fn is_safe_rate_of_change(report: &[i32], tolerance: &Tolerance) -> bool {
+    levels_to_remove(report, tolerance).is_some()
+}
+
+fn parse_reports(input: &str) -> Vec<Vec<i32>> {
+    input
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .map(|num| num.parse::<i32>().unwrap())
+                .collect()
+        })
+        .collect()
+}
 
-    combinations
+fn count_safe(reports: &[Vec<i32>], tolerance: &Tolerance) -> usize {
+    reports
+        .iter()
+        .filter(|report| is_safe_rate_of_change(report, tolerance))
+        .count()
 }
 
 impl AdventDay for Day02 {
@@ -50,46 +120,13 @@ impl AdventDay for Day02 {
     }
 
     fn part_one(&self) -> String {
-        let reports: Vec<Vec<i32>> = self
-            .input
-            .lines()
-            .map(|line| {
-                line.split_whitespace()
-                    .map(|num| num.parse::<i32>().unwrap())
-                    .collect()
-            })
-            .collect();
-
-        let count = reports
-            .iter()
-            .filter(|report| is_safe_rate_of_change(report.to_vec()))
-            .count();
-
-        count.to_string()
+        let reports = parse_reports(&self.input);
+        count_safe(&reports, &STRICT).to_string()
     }
 
     fn part_two(&self) -> String {
-        let reports: Vec<Vec<i32>> = self
-            .input
-            .lines()
-            .map(|line| {
-                line.split_whitespace()
-                    .map(|num| num.parse::<i32>().unwrap())
-                    .collect()
-            })
-            .collect();
-
-        let count = reports
-            .iter()
-            .filter(|report| {
-                let combinations = vec_combinations(report.to_vec());
-                combinations
-                    .iter()
-                    .any(|combination| is_safe_rate_of_change(combination.to_vec()))
-            })
-            .count();
-
-        count.to_string()
+        let reports = parse_reports(&self.input);
+        count_safe(&reports, &DAMPENED).to_string()
     }
 }
 
@@ -118,23 +155,51 @@ mod tests {
 
     #[test]
     fn is_safe_rate_of_change_test() {
-        assert!(is_safe_rate_of_change(vec![1, 2, 3, 4, 5]), "1, 2, 3, 4, 5");
-        assert!(is_safe_rate_of_change(vec![6, 4, 2, 1]), "6, 4, 2, 1");
         assert!(
-            !is_safe_rate_of_change(vec![1, 2, 7, 8, 9]),
+            is_safe_rate_of_change(&[1, 2, 3, 4, 5], &STRICT),
+            "1, 2, 3, 4, 5"
+        );
+        assert!(is_safe_rate_of_change(&[6, 4, 2, 1], &STRICT), "6, 4, 2, 1");
+        assert!(
+            !is_safe_rate_of_change(&[1, 2, 7, 8, 9], &STRICT),
             "1, 2, 7, 8, 9"
         );
         assert!(
-            !is_safe_rate_of_change(vec![9, 7, 6, 2, 1]),
+            !is_safe_rate_of_change(&[9, 7, 6, 2, 1], &STRICT),
             "9, 7, 6, 2, 1"
         );
         assert!(
-            !is_safe_rate_of_change(vec![1, 3, 2, 4, 5]),
+            !is_safe_rate_of_change(&[1, 3, 2, 4, 5], &STRICT),
             "1, 3, 2, 4, 5"
         );
         assert!(
-            !is_safe_rate_of_change(vec![8, 6, 4, 4, 1]),
+            !is_safe_rate_of_change(&[8, 6, 4, 4, 1], &STRICT),
             "8, 6, 4, 4, 1"
         );
     }
+
+    #[test]
+    fn levels_to_remove_test() {
+        assert_eq!(levels_to_remove(&[1, 3, 2, 4, 5], &DAMPENED), Some(vec![2]));
+        assert_eq!(levels_to_remove(&[8, 6, 4, 4, 1], &DAMPENED), Some(vec![3]));
+        assert_eq!(levels_to_remove(&[1, 2, 7, 8, 9], &DAMPENED), None);
+        assert_eq!(levels_to_remove(&[7, 6, 4, 2, 1], &STRICT), Some(vec![]));
+
+        let wide = Tolerance {
+            min_step: 1,
+            max_step: 5,
+            max_removals: 2,
+        };
+        assert_eq!(levels_to_remove(&[1, 2, 7, 8, 9], &wide), Some(vec![]));
+        assert_eq!(levels_to_remove(&[9, 1, 20, 2, 3, 30], &wide), None);
+
+        let wider = Tolerance {
+            max_removals: 3,
+            ..wide
+        };
+        assert_eq!(
+            levels_to_remove(&[9, 1, 20, 2, 3, 30], &wider),
+            Some(vec![0, 2, 5])
+        );
+    }
 }
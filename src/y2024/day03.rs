@@ -1,54 +1,160 @@
-use regex::Regex;
-
 use crate::utils::AdventDay;
 
-pub struct Day03 {
-    input: String,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Mul(u32, u32),
+    Do,
+    Dont,
 }
 
-impl AdventDay for Day03 {
-    fn new(input: String) -> Self {
-        Self { input }
+/// An instruction found in the corrupted memory, with the byte range it
+/// was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub instruction: Instruction,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Tries to read one instruction at the start of the input, returning it
+/// together with the number of bytes it takes up.
+pub type Parser = fn(&[u8]) -> Option<(Instruction, usize)>;
+
+/// Every instruction the scanner knows. A new instruction is a variant of
+/// `Instruction` plus a parser here.
+pub const PARSERS: &[Parser] = &[parse_mul, parse_do, parse_dont];
+
+/// Reads 1 to 3 digits, returning the number and the digit count.
+fn parse_operand(input: &[u8]) -> Option<(u32, usize)> {
+    let digits = input
+        .iter()
+        .take(4)
+        .take_while(|byte| byte.is_ascii_digit())
+        .count();
+    if !(1..=3).contains(&digits) {
+        return None;
     }
 
-    fn part_one(&self) -> String {
-        let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
+    let value = input[..digits]
+        .iter()
+        .fold(0, |value, byte| value * 10 + (byte - b'0') as u32);
 
-        let mut result = 0;
+    Some((value, digits))
+}
 
-        for cap in re.captures_iter(&self.input) {
-            let a: i32 = cap[1].parse().unwrap();
-            let b: i32 = cap[2].parse().unwrap();
-            result += a * b;
-        }
+fn parse_mul(input: &[u8]) -> Option<(Instruction, usize)> {
+    let mut len = b"mul(".len();
+    if !input.starts_with(b"mul(") {
+        return None;
+    }
 
-        result.to_string()
+    let (a, digits) = parse_operand(&input[len..])?;
+    len += digits;
+    if input.get(len) != Some(&b',') {
+        return None;
     }
+    len += 1;
 
-    fn part_two(&self) -> String {
-        let re = Regex::new(r"mul\((\d+),(\d+)\)|do\(\)|don't\(\)").unwrap();
+    let (b, digits) = parse_operand(&input[len..])?;
+    len += digits;
+    if input.get(len) != Some(&b')') {
+        return None;
+    }
 
-        let mut result = 0;
-        let mut skip = false;
+    Some((Instruction::Mul(a, b), len + 1))
+}
 
-        for cap in re.captures_iter(&self.input) {
-            match cap.get(0).map(|m| m.as_str()) {
-                Some("do()") => {
-                    skip = false;
-                }
-                Some("don't()") => {
-                    skip = true;
-                }
-                Some(_) if !skip => {
-                    let a: i32 = cap[1].parse().unwrap();
-                    let b: i32 = cap[2].parse().unwrap();
-                    result += a * b;
+fn parse_do(input: &[u8]) -> Option<(Instruction, usize)> {
+    input
+        .starts_with(b"do()")
+        .then_some((Instruction::Do, b"do()".len()))
+}
+
+fn parse_dont(input: &[u8]) -> Option<(Instruction, usize)> {
+    input
+        .starts_with(b"don't()")
+        .then_some((Instruction::Dont, b"don't()".len()))
+}
+
+/// Scans the memory left to right, trying every parser at each byte and
+/// skipping over whatever none of them recognise.
+pub fn tokenize(input: &str, parsers: &[Parser]) -> Vec<Token> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match parsers.iter().find_map(|parse| parse(&bytes[i..])) {
+            Some((instruction, len)) => {
+                tokens.push(Token {
+                    instruction,
+                    start: i,
+                    end: i + len,
+                });
+                i += len;
+            }
+            None => i += 1,
+        }
+    }
+
+    tokens
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Evaluation {
+    pub sum: u64,
+    /// Byte ranges between a `don't()` and the next `do()`
+    pub disabled: Vec<(usize, usize)>,
+    /// Start of a `don't()` that no `do()` follows, so everything after it
+    /// is disabled
+    pub disabled_from: Option<usize>,
+    /// Multiplications inside the disabled ranges
+    pub skipped: Vec<Token>,
+}
+
+/// Sums the multiplications. When `conditionals` is false, `do()` and
+/// `don't()` are ignored and every multiplication counts.
+pub fn evaluate(tokens: &[Token], conditionals: bool) -> Evaluation {
+    let mut evaluation = Evaluation::default();
+
+    for token in tokens {
+        match token.instruction {
+            Instruction::Do if conditionals => {
+                if let Some(start) = evaluation.disabled_from.take() {
+                    evaluation.disabled.push((start, token.end));
                 }
-                _ => {}
             }
+            Instruction::Dont if conditionals => {
+                evaluation.disabled_from.get_or_insert(token.start);
+            }
+            Instruction::Mul(..) if evaluation.disabled_from.is_some() => {
+                evaluation.skipped.push(*token)
+            }
+            Instruction::Mul(a, b) => evaluation.sum += (a * b) as u64,
+            Instruction::Do | Instruction::Dont => {}
         }
+    }
+
+    evaluation
+}
+
+pub struct Day03 {
+    input: String,
+}
+
+impl AdventDay for Day03 {
+    fn new(input: String) -> Self {
+        Self { input }
+    }
+
+    fn part_one(&self) -> String {
+        let tokens = tokenize(&self.input, PARSERS);
+        evaluate(&tokens, false).sum.to_string()
+    }
 
-        result.to_string()
+    fn part_two(&self) -> String {
+        let tokens = tokenize(&self.input, PARSERS);
+        evaluate(&tokens, true).sum.to_string()
     }
 }
 
@@ -71,4 +177,55 @@ mod tests {
         let day03 = Day03::new(DATA_2.to_string());
         assert_eq!(day03.part_two(), "48");
     }
+
+    #[test]
+    fn tokenize_keeps_offsets_and_operand_limits() {
+        let tokens = tokenize("mul(1,2)don't()mul(1234,5)do()", PARSERS);
+
+        assert_eq!(
+            tokens,
+            [
+                Token {
+                    instruction: Instruction::Mul(1, 2),
+                    start: 0,
+                    end: 8,
+                },
+                Token {
+                    instruction: Instruction::Dont,
+                    start: 8,
+                    end: 15,
+                },
+                Token {
+                    instruction: Instruction::Do,
+                    start: 26,
+                    end: 30,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn evaluate_reports_disabled_spans() {
+        let tokens = tokenize(DATA_2, PARSERS);
+        let evaluation = evaluate(&tokens, true);
+
+        assert_eq!(evaluation.sum, 48);
+        assert_eq!(evaluation.disabled, [(20, 63)]);
+        assert_eq!(evaluation.disabled_from, None);
+        assert_eq!(
+            evaluation
+                .skipped
+                .iter()
+                .map(|token| token.instruction)
+                .collect::<Vec<_>>(),
+            [Instruction::Mul(5, 5), Instruction::Mul(11, 8)]
+        );
+
+        let tokens = tokenize("mul(1,2)don't()mul(3,4)", PARSERS);
+        let evaluation = evaluate(&tokens, true);
+
+        assert_eq!(evaluation.sum, 2);
+        assert!(evaluation.disabled.is_empty());
+        assert_eq!(evaluation.disabled_from, Some(8));
+    }
 }
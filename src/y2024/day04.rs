@@ -1,147 +1,197 @@
 use crate::utils::AdventDay;
 
-pub struct Day04 {
-    input: String,
+type Position = (usize, usize);
+
+/// Row and column steps for all eight directions, clockwise from east.
+pub const DIRECTIONS: [(isize, isize); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+
+/// The X-MAS cross, with `.` matching any letter.
+const X_MAS: &str = "M.S\n.A.\nM.S";
+
+/// A 2D pattern of letters, where `.` matches anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stencil {
+    height: usize,
+    width: usize,
+    cells: Vec<(usize, usize, char)>,
 }
 
-impl AdventDay for Day04 {
-    fn new(input: String) -> Self {
-        Self { input }
+impl Stencil {
+    pub fn new(pattern: &str) -> Self {
+        let rows: Vec<&str> = pattern.lines().collect();
+        let cells = rows
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter(|&(_, c)| c != '.')
+                    .map(move |(j, c)| (i, j, c))
+            })
+            .collect();
+
+        Self {
+            height: rows.len(),
+            width: rows
+                .iter()
+                .map(|row| row.chars().count())
+                .max()
+                .unwrap_or(0),
+            cells,
+        }
     }
 
-    fn part_one(&self) -> String {
-        let matrix: Vec<Vec<char>> = self
-            .input
-            .lines()
-            .map(|line| line.chars().collect())
+    /// The stencil turned 90 degrees clockwise.
+    fn rotate(&self) -> Self {
+        let mut cells: Vec<_> = self
+            .cells
+            .iter()
+            .map(|&(i, j, c)| (j, self.height - 1 - i, c))
             .collect();
+        cells.sort();
 
-        let mut count = 0;
-
-        for (i, row) in matrix.iter().enumerate() {
-            for (j, _) in row.iter().enumerate() {
-                // Check forward
-                if j + 3 < row.len()
-                    && row[j] == 'X'
-                    && row[j + 1] == 'M'
-                    && row[j + 2] == 'A'
-                    && row[j + 3] == 'S'
-                {
-                    count += 1;
-                }
-
-                // Check down
-                if i + 3 < matrix.len()
-                    && matrix[i][j] == 'X'
-                    && matrix[i + 1][j] == 'M'
-                    && matrix[i + 2][j] == 'A'
-                    && matrix[i + 3][j] == 'S'
-                {
-                    count += 1;
-                }
+        Self {
+            height: self.width,
+            width: self.height,
+            cells,
+        }
+    }
 
-                // Check up
-                if i >= 3
-                    && matrix[i][j] == 'X'
-                    && matrix[i - 1][j] == 'M'
-                    && matrix[i - 2][j] == 'A'
-                    && matrix[i - 3][j] == 'S'
-                {
-                    count += 1;
-                }
+    /// All distinct rotations, so symmetric stencils are not matched twice.
+    fn rotations(&self) -> Vec<Self> {
+        let mut rotations: Vec<Self> = Vec::new();
+        let mut current = self.clone();
 
-                // Check backward
-                if j >= 3
-                    && row[j] == 'X'
-                    && row[j - 1] == 'M'
-                    && row[j - 2] == 'A'
-                    && row[j - 3] == 'S'
-                {
-                    count += 1;
-                }
+        for _ in 0..4 {
+            if !rotations.contains(&current) {
+                rotations.push(current.clone());
+            }
+            current = current.rotate();
+        }
 
-                // Check diagonal down-right
-                if i + 3 < matrix.len()
-                    && j + 3 < row.len()
-                    && matrix[i][j] == 'X'
-                    && matrix[i + 1][j + 1] == 'M'
-                    && matrix[i + 2][j + 2] == 'A'
-                    && matrix[i + 3][j + 3] == 'S'
-                {
-                    count += 1;
-                }
+        rotations
+    }
+}
 
-                // Check diagonal down-left
-                if i + 3 < matrix.len()
-                    && j >= 3
-                    && matrix[i][j] == 'X'
-                    && matrix[i + 1][j - 1] == 'M'
-                    && matrix[i + 2][j - 2] == 'A'
-                    && matrix[i + 3][j - 3] == 'S'
-                {
-                    count += 1;
-                }
+pub struct WordSearch {
+    grid: Vec<Vec<char>>,
+}
 
-                // Check diagonal up-right
-                if i >= 3
-                    && j + 3 < row.len()
-                    && matrix[i][j] == 'X'
-                    && matrix[i - 1][j + 1] == 'M'
-                    && matrix[i - 2][j + 2] == 'A'
-                    && matrix[i - 3][j + 3] == 'S'
-                {
-                    count += 1;
-                }
+impl WordSearch {
+    pub fn new(input: &str) -> Self {
+        Self {
+            grid: input.lines().map(|line| line.chars().collect()).collect(),
+        }
+    }
 
-                // Check diagonal up-left
-                if i >= 3
-                    && j >= 3
-                    && matrix[i][j] == 'X'
-                    && matrix[i - 1][j - 1] == 'M'
-                    && matrix[i - 2][j - 2] == 'A'
-                    && matrix[i - 3][j - 3] == 'S'
-                {
-                    count += 1;
-                }
-            }
+    fn get(&self, i: isize, j: isize) -> Option<char> {
+        if i < 0 || j < 0 {
+            return None;
         }
 
-        count.to_string()
+        self.grid.get(i as usize)?.get(j as usize).copied()
     }
 
-    fn part_two(&self) -> String {
-        let matrix: Vec<Vec<char>> = self
-            .input
-            .lines()
-            .map(|line| line.chars().collect())
-            .collect();
+    /// Every occurrence of `word` read in one of `directions`, as the cells
+    /// of its letters in reading order.
+    pub fn find_word(&self, word: &str, directions: &[(isize, isize)]) -> Vec<Vec<Position>> {
+        let letters: Vec<char> = word.chars().collect();
+        let mut matches = Vec::new();
 
-        let mut count = 0;
+        for (i, row) in self.grid.iter().enumerate() {
+            for j in 0..row.len() {
+                for &(di, dj) in directions {
+                    let cells: Vec<(isize, isize)> = (0..letters.len() as isize)
+                        .map(|k| (i as isize + k * di, j as isize + k * dj))
+                        .collect();
 
-        for (i, row) in matrix.iter().enumerate() {
-            for (j, &c) in row.iter().enumerate() {
-                if c != 'A' {
-                    continue;
-                }
+                    let is_match = cells
+                        .iter()
+                        .zip(&letters)
+                        .all(|(&(ci, cj), &letter)| self.get(ci, cj) == Some(letter));
 
-                let is_within = i > 0 && j > 0 && i + 1 < matrix.len() && j + 1 < row.len();
-                if !is_within {
-                    continue;
+                    if is_match {
+                        matches.push(
+                            cells
+                                .into_iter()
+                                .map(|(ci, cj)| (ci as usize, cj as usize))
+                                .collect(),
+                        );
+                    }
                 }
+            }
+        }
+
+        matches
+    }
+
+    /// Every placement of `stencil`, optionally in any rotation, as the cells
+    /// of its letters. An empty stencil or grid has no placements.
+    pub fn find_stencil(&self, stencil: &Stencil, rotate: bool) -> Vec<Vec<Position>> {
+        if stencil.cells.is_empty() || self.grid.is_empty() {
+            return Vec::new();
+        }
 
-                let a = format!("{}{}", matrix[i + 1][j + 1], matrix[i - 1][j - 1]);
-                let b = format!("{}{}", matrix[i - 1][j + 1], matrix[i + 1][j - 1]);
+        let stencils = if rotate {
+            stencil.rotations()
+        } else {
+            vec![stencil.clone()]
+        };
+        let mut matches = Vec::new();
 
-                let a_is_x_mas = matches!(a.as_str(), "MS" | "SM");
-                let b_is_x_mas = matches!(b.as_str(), "MS" | "SM");
+        for stencil in &stencils {
+            for i in 0..(self.grid.len() + 1).saturating_sub(stencil.height) {
+                for j in 0..(self.grid[i].len() + 1).saturating_sub(stencil.width) {
+                    let is_match = stencil.cells.iter().all(|&(si, sj, c)| {
+                        self.get((i + si) as isize, (j + sj) as isize) == Some(c)
+                    });
 
-                if a_is_x_mas && b_is_x_mas {
-                    count += 1;
+                    if is_match {
+                        matches.push(
+                            stencil
+                                .cells
+                                .iter()
+                                .map(|&(si, sj, _)| (i + si, j + sj))
+                                .collect(),
+                        );
+                    }
                 }
             }
         }
 
-        count.to_string()
+        matches
+    }
+}
+
+pub struct Day04 {
+    input: String,
+}
+
+impl AdventDay for Day04 {
+    fn new(input: String) -> Self {
+        Self { input }
+    }
+
+    fn part_one(&self) -> String {
+        let search = WordSearch::new(&self.input);
+        search.find_word("XMAS", &DIRECTIONS).len().to_string()
+    }
+
+    fn part_two(&self) -> String {
+        let search = WordSearch::new(&self.input);
+        search
+            .find_stencil(&Stencil::new(X_MAS), true)
+            .len()
+            .to_string()
     }
 }
 
@@ -179,4 +229,53 @@ MXMXAXMASX"#;
         let day04 = Day04::new(DATA.to_string());
         assert_eq!(day04.part_two(), "9");
     }
+
+    #[test]
+    fn find_word_positions() {
+        let search = WordSearch::new(SIMPLE_DATA);
+
+        assert_eq!(
+            search.find_word("XMAS", &[(0, 1)]),
+            [vec![(3, 0), (3, 1), (3, 2), (3, 3)]]
+        );
+        assert_eq!(
+            search.find_word("XMAS", &[(0, -1)]),
+            [vec![(1, 4), (1, 3), (1, 2), (1, 1)]]
+        );
+    }
+
+    #[test]
+    fn find_stencil_rotations() {
+        let stencil = Stencil::new(X_MAS);
+
+        let search = WordSearch::new("M.S\n.A.\nM.S\n.A.\nM.S");
+        assert_eq!(
+            search.find_stencil(&stencil, false),
+            [
+                vec![(0, 0), (0, 2), (1, 1), (2, 0), (2, 2)],
+                vec![(2, 0), (2, 2), (3, 1), (4, 0), (4, 2)]
+            ]
+        );
+
+        let search = WordSearch::new("M.M\n.A.\nS.S");
+        assert!(search.find_stencil(&stencil, false).is_empty());
+        assert_eq!(
+            search.find_stencil(&stencil, true),
+            [vec![(0, 0), (0, 2), (1, 1), (2, 0), (2, 2)]]
+        );
+        assert_eq!(Stencil::new("A.A\n.A.\nA.A").rotations().len(), 1);
+    }
+
+    #[test]
+    fn find_stencil_empty() {
+        let stencil = Stencil::new(X_MAS);
+
+        assert!(WordSearch::new("").find_stencil(&stencil, true).is_empty());
+        assert!(WordSearch::new("")
+            .find_stencil(&Stencil::new(""), true)
+            .is_empty());
+        assert!(WordSearch::new("M.S\n.A.")
+            .find_stencil(&Stencil::new(""), false)
+            .is_empty());
+    }
 }
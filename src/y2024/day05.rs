@@ -2,19 +2,132 @@ use crate::utils::AdventDay;
 use itertools::Itertools;
 use std::collections::HashMap;
 
-fn is_safe(rules: &HashMap<i32, Vec<i32>>, update: &[i32]) -> bool {
-    for (i, value) in update.iter().enumerate() {
-        if let Some(values) = rules.get(value) {
-            if values.iter().any(|v| update[0..i].contains(v)) {
-                return false;
+/// Page ordering rules as a precedence graph, mapping every page to the pages
+/// that must come after it.
+mod ordering {
+    use itertools::Itertools;
+    use std::{
+        cmp::Reverse,
+        collections::{BinaryHeap, HashMap},
+        fmt,
+    };
+
+    pub type Rules = HashMap<i32, Vec<i32>>;
+
+    /// Pages that must come before each other, so no order satisfies the rules.
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct Cycle {
+        pub pages: Vec<i32>,
+    }
+
+    impl fmt::Display for Cycle {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let pages = self.pages.iter().map(|page| page.to_string()).join(" -> ");
+            write!(f, "pages {} -> {} form a cycle", pages, self.pages[0])
+        }
+    }
+
+    fn positions(update: &[i32]) -> HashMap<i32, usize> {
+        update
+            .iter()
+            .enumerate()
+            .map(|(i, &page)| (page, i))
+            .collect()
+    }
+
+    /// Whether every rule between two pages of the update is respected. Each
+    /// page and each of its rules is looked at once.
+    pub fn is_ordered(rules: &Rules, update: &[i32]) -> bool {
+        let positions = positions(update);
+
+        update.iter().enumerate().all(|(i, page)| {
+            rules.get(page).is_none_or(|after| {
+                after
+                    .iter()
+                    .all(|other| positions.get(other).is_none_or(|&j| i < j))
+            })
+        })
+    }
+
+    /// Topologically sorts the update using only the rules between its own
+    /// pages. Ties keep the order of the update, since the earliest page
+    /// that is free to go next always goes first.
+    pub fn sort(rules: &Rules, update: &[i32]) -> Result<Vec<i32>, Cycle> {
+        let positions = positions(update);
+        let edges: Vec<Vec<usize>> = update
+            .iter()
+            .map(|page| {
+                rules
+                    .get(page)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|other| positions.get(other).copied())
+                    .collect()
+            })
+            .collect();
+
+        let mut in_degree = vec![0; update.len()];
+        for &j in edges.iter().flatten() {
+            in_degree[j] += 1;
+        }
+
+        let mut queue: BinaryHeap<Reverse<usize>> = (0..update.len())
+            .filter(|&i| in_degree[i] == 0)
+            .map(Reverse)
+            .collect();
+        let mut sorted = Vec::with_capacity(update.len());
+
+        while let Some(Reverse(i)) = queue.pop() {
+            sorted.push(update[i]);
+            for &j in &edges[i] {
+                in_degree[j] -= 1;
+                if in_degree[j] == 0 {
+                    queue.push(Reverse(j));
+                }
             }
         }
+
+        if sorted.len() == update.len() {
+            Ok(sorted)
+        } else {
+            Err(find_cycle(update, &edges, &in_degree))
+        }
     }
 
-    true
+    // Every page left with a positive in-degree has a predecessor that is also
+    // left, so walking predecessors must eventually repeat a page.
+    fn find_cycle(update: &[i32], edges: &[Vec<usize>], in_degree: &[usize]) -> Cycle {
+        let mut predecessor = vec![None; update.len()];
+        for (i, targets) in edges.iter().enumerate() {
+            if in_degree[i] == 0 {
+                continue;
+            }
+            for &j in targets {
+                predecessor[j].get_or_insert(i);
+            }
+        }
+
+        let mut seen = vec![false; update.len()];
+        let mut current = (0..update.len()).find(|&i| in_degree[i] > 0).unwrap();
+        while !seen[current] {
+            seen[current] = true;
+            current = predecessor[current].unwrap();
+        }
+
+        let start = current;
+        let mut pages = vec![update[start]];
+        current = predecessor[start].unwrap();
+        while current != start {
+            pages.push(update[current]);
+            current = predecessor[current].unwrap();
+        }
+        pages.reverse();
+
+        Cycle { pages }
+    }
 }
 
-fn parse_rules(str: &str) -> HashMap<i32, Vec<i32>> {
+fn parse_rules(str: &str) -> ordering::Rules {
     let mut rules_map: HashMap<i32, Vec<i32>> = HashMap::new();
     for rule in str.lines() {
         let (key, value) = rule
@@ -31,25 +144,6 @@ fn parse_rules(str: &str) -> HashMap<i32, Vec<i32>> {
     rules_map
 }
 
-fn fix_update(rules: &HashMap<i32, Vec<i32>>, update: &[i32]) -> Vec<i32> {
-    let mut fixed = update.to_owned();
-
-    fixed.sort_by(|a, b| {
-        let a_deps = rules.get(a).map(|v| v.as_slice()).unwrap_or(&[]);
-        let b_deps = rules.get(b).map(|v| v.as_slice()).unwrap_or(&[]);
-
-        if a_deps.contains(b) {
-            std::cmp::Ordering::Greater
-        } else if b_deps.contains(a) {
-            std::cmp::Ordering::Less
-        } else {
-            std::cmp::Ordering::Equal
-        }
-    });
-
-    fixed
-}
-
 fn parse_updates(str: &str) -> Vec<Vec<i32>> {
     str.lines()
         .map(|line| {
@@ -76,7 +170,7 @@ impl AdventDay for Day05 {
 
         updates
             .iter()
-            .filter(|update| is_safe(&rules, update))
+            .filter(|update| ordering::is_ordered(&rules, update))
             .map(|update| update[update.len() / 2])
             .sum::<i32>()
             .to_string()
@@ -86,15 +180,16 @@ impl AdventDay for Day05 {
         let (rules_str, updates_str) = self.input.split("\n\n").collect_tuple().unwrap();
         let rules = parse_rules(rules_str);
         let updates = parse_updates(updates_str);
-        updates
+        let sum = updates
             .iter()
-            .filter(|update| !is_safe(&rules, update))
-            .map(|update| {
-                let fixed = fix_update(&rules, update);
-                fixed[fixed.len() / 2]
-            })
-            .sum::<i32>()
-            .to_string()
+            .filter(|update| !ordering::is_ordered(&rules, update))
+            .map(|update| ordering::sort(&rules, update).map(|fixed| fixed[fixed.len() / 2]))
+            .sum::<Result<i32, ordering::Cycle>>();
+
+        match sum {
+            Ok(sum) => sum.to_string(),
+            Err(cycle) => format!("cannot order update: {}", cycle),
+        }
     }
 }
 
@@ -142,4 +237,16 @@ mod tests {
         let day05 = Day05::new(DATA.to_string());
         assert_eq!(day05.part_two(), "123");
     }
+
+    #[test]
+    fn sort_reports_cycle() {
+        let rules = parse_rules("1|2\n2|3\n3|1\n3|4");
+
+        assert!(!ordering::is_ordered(&rules, &[2, 1, 4]));
+        assert_eq!(ordering::sort(&rules, &[2, 1, 4]), Ok(vec![1, 2, 4]));
+
+        let cycle = ordering::sort(&rules, &[4, 3, 2, 1]).unwrap_err();
+        assert_eq!(cycle.pages, vec![1, 2, 3]);
+        assert_eq!(cycle.to_string(), "pages 1 -> 2 -> 3 -> 1 form a cycle");
+    }
 }
@@ -1,7 +1,11 @@
+use crate::utils::visualize::{Frame, Palette, Visualize};
 use crate::utils::AdventDay;
 use itertools::Itertools;
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
+type Position = (usize, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Direction {
     North,
     East,
@@ -18,68 +22,186 @@ impl Direction {
             Self::West => Self::North,
         }
     }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+
+    fn arrow(&self) -> char {
+        match self {
+            Self::North => '^',
+            Self::East => '>',
+            Self::South => 'v',
+            Self::West => '<',
+        }
+    }
 }
 
+/// Walks the guard one cell at a time, yielding every position together with
+/// the direction the guard was facing when it stepped onto it. The start
+/// position is yielded first.
 struct Guard<'a> {
-    map: &'a Vec<Vec<char>>,
-    i: i32,
-    j: i32,
-    moves: Vec<(i32, i32)>,
+    map: &'a [Vec<char>],
+    position: Position,
     direction: Direction,
-    done: bool,
+    started: bool,
 }
 
 impl<'a> Guard<'a> {
-    fn new(map: &'a Vec<Vec<char>>) -> Self {
-        let (i, j) = find_start(map);
-
+    fn new(map: &'a [Vec<char>]) -> Self {
         Self {
             map,
-            i,
-            j,
+            position: find_start(map),
             direction: Direction::North,
-            moves: vec![(i, j)],
-            done: false,
+            started: false,
         }
     }
 }
 
 impl Iterator for Guard<'_> {
-    type Item = (i32, i32);
+    type Item = (Position, Direction);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.done {
-            return None;
+        if !self.started {
+            self.started = true;
+            return Some((self.position, self.direction));
         }
 
-        let (new_i, new_j) = match self.direction {
-            Direction::North => (self.i - 1, self.j),
-            Direction::East => (self.i, self.j + 1),
-            Direction::South => (self.i + 1, self.j),
-            Direction::West => (self.i, self.j - 1),
-        };
+        loop {
+            let (new_i, new_j) = step(self.position, self.direction, self.map)?;
 
-        let is_outside = new_i < 0
-            || new_j < 0
-            || new_i >= self.map.len() as i32
-            || new_j >= self.map[0].len() as i32;
-        if is_outside {
-            self.done = true;
-            return None;
+            if self.map[new_i][new_j] == '#' {
+                self.direction = self.direction.next();
+                continue;
+            }
+
+            self.position = (new_i, new_j);
+            return Some((self.position, self.direction));
         }
+    }
+}
 
-        if self.map[new_i as usize][new_j as usize] == '#'
-            || self.map[new_i as usize][new_j as usize] == 'O'
-        {
-            self.direction = self.direction.next();
-            return self.next();
+/// The neighbouring cell in `direction`, or `None` if it is outside the map.
+fn step((i, j): Position, direction: Direction, map: &[Vec<char>]) -> Option<Position> {
+    let (new_i, new_j) = match direction {
+        Direction::North => (i.checked_sub(1)?, j),
+        Direction::East => (i, j + 1),
+        Direction::South => (i + 1, j),
+        Direction::West => (i, j.checked_sub(1)?),
+    };
+
+    (new_i < map.len() && new_j < map[0].len()).then_some((new_i, new_j))
+}
+
+/// For every cell and direction, the cell where the guard stops in front of
+/// the next obstacle, or `None` if the guard walks off the map instead.
+struct JumpTable {
+    cols: usize,
+    stops: Vec<[Option<Position>; 4]>,
+}
+
+impl JumpTable {
+    fn new(map: &[Vec<char>]) -> Self {
+        let (rows, cols) = (map.len(), map[0].len());
+        let mut stops = vec![[None; 4]; rows * cols];
+
+        for j in 0..cols {
+            let mut stop = None;
+            for i in 0..rows {
+                if map[i][j] == '#' {
+                    stop = Some((i + 1, j));
+                } else {
+                    stops[i * cols + j][Direction::North.index()] = stop;
+                }
+            }
+
+            let mut stop = None;
+            for i in (0..rows).rev() {
+                if map[i][j] == '#' {
+                    stop = i.checked_sub(1).map(|i| (i, j));
+                } else {
+                    stops[i * cols + j][Direction::South.index()] = stop;
+                }
+            }
         }
 
-        self.i = new_i;
-        self.j = new_j;
-        self.moves.push((self.i, self.j));
+        for i in 0..rows {
+            let mut stop = None;
+            for j in 0..cols {
+                if map[i][j] == '#' {
+                    stop = Some((i, j + 1));
+                } else {
+                    stops[i * cols + j][Direction::West.index()] = stop;
+                }
+            }
 
-        Some((self.i, self.j))
+            let mut stop = None;
+            for j in (0..cols).rev() {
+                if map[i][j] == '#' {
+                    stop = j.checked_sub(1).map(|j| (i, j));
+                } else {
+                    stops[i * cols + j][Direction::East.index()] = stop;
+                }
+            }
+        }
+
+        Self { cols, stops }
+    }
+
+    /// Like the precomputed stop, but also taking one extra obstacle into
+    /// account.
+    fn next_stop(
+        &self,
+        (i, j): Position,
+        direction: Direction,
+        (oi, oj): Position,
+    ) -> Option<Position> {
+        let stop = self.stops[i * self.cols + j][direction.index()];
+
+        let blocked_early = match direction {
+            Direction::North => oj == j && oi < i && stop.is_none_or(|(si, _)| oi >= si),
+            Direction::South => oj == j && oi > i && stop.is_none_or(|(si, _)| oi <= si),
+            Direction::West => oi == i && oj < j && stop.is_none_or(|(_, sj)| oj >= sj),
+            Direction::East => oi == i && oj > j && stop.is_none_or(|(_, sj)| oj <= sj),
+        };
+
+        if !blocked_early {
+            return stop;
+        }
+
+        Some(match direction {
+            Direction::North => (oi + 1, j),
+            Direction::South => (oi - 1, j),
+            Direction::West => (i, oj + 1),
+            Direction::East => (i, oj - 1),
+        })
+    }
+}
+
+/// Whether the guard, starting at `position` facing `direction`, ends up in
+/// a loop once `obstacle` is added. The guard loops exactly when it turns at
+/// the same cell in the same direction twice. `seen` holds the stamp of the
+/// last run that visited each (cell, direction) state, so it never has to be
+/// cleared between runs.
+fn is_loop(
+    table: &JumpTable,
+    (mut position, mut direction): (Position, Direction),
+    obstacle: Position,
+    seen: &mut [usize],
+    stamp: usize,
+) -> bool {
+    loop {
+        match table.next_stop(position, direction, obstacle) {
+            Some(stop) => position = stop,
+            None => return false,
+        }
+        direction = direction.next();
+
+        let state = (position.0 * table.cols + position.1) * 4 + direction.index();
+        if seen[state] == stamp {
+            return true;
+        }
+        seen[state] = stamp;
     }
 }
 
@@ -90,11 +212,11 @@ fn parse_map(input: &str) -> Vec<Vec<char>> {
         .collect_vec()
 }
 
-fn find_start(map: &[Vec<char>]) -> (i32, i32) {
+fn find_start(map: &[Vec<char>]) -> Position {
     for (i, row) in map.iter().enumerate() {
         for (j, cell) in row.iter().enumerate() {
             if *cell == '^' {
-                return (i as i32, j as i32);
+                return (i, j);
             }
         }
     }
@@ -114,40 +236,35 @@ impl AdventDay for Day06 {
     fn part_one(&self) -> String {
         let map = parse_map(&self.input);
         let guard = Guard::new(&map);
-        let moves: Vec<(i32, i32)> = guard.collect();
-        let distinct_moves: HashSet<(i32, i32)> = moves.into_iter().collect();
+        let distinct_moves: HashSet<Position> = guard.map(|(position, _)| position).collect();
         distinct_moves.len().to_string()
     }
 
     fn part_two(&self) -> String {
         let map = parse_map(&self.input);
-        let guard = Guard::new(&map);
-        let moves: Vec<(i32, i32)> = guard.collect();
-        let distinct_moves: HashSet<(i32, i32)> = moves.into_iter().collect();
+        let table = JumpTable::new(&map);
+        let path: Vec<(Position, Direction)> = Guard::new(&map).collect();
+
+        let mut tried = vec![false; map.len() * map[0].len()];
+        let mut seen = vec![usize::MAX; map.len() * map[0].len() * 4];
+        let (start, _) = path[0];
+        tried[start.0 * table.cols + start.1] = true;
+
         let mut loops = 0;
 
-        for (i, j) in distinct_moves {
-            if map[i as usize][j as usize] == '^' {
+        // An obstacle only changes the path from the first time the guard
+        // would have walked onto it, so each candidate is simulated from the
+        // step right before that.
+        for (stamp, (&(from, _), &(obstacle, direction))) in path.iter().tuple_windows().enumerate()
+        {
+            let cell = obstacle.0 * table.cols + obstacle.1;
+            if tried[cell] {
                 continue;
             }
+            tried[cell] = true;
 
-            let mut map_with_o = map.clone();
-            map_with_o[i as usize][j as usize] = 'O';
-            let guard = Guard::new(&map_with_o);
-            let mut visited: HashMap<(i32, i32), i32> = HashMap::new();
-
-            for (k, l) in guard {
-                if visited.contains_key(&(k, l)) {
-                    let visited_count = visited[&(k, l)];
-                    if visited_count > 3 {
-                        loops += 1;
-                        break;
-                    }
-
-                    visited.insert((k, l), visited_count + 1);
-                } else {
-                    visited.insert((k, l), 1);
-                }
+            if is_loop(&table, (from, direction), obstacle, &mut seen, stamp) {
+                loops += 1;
             }
         }
 
@@ -155,6 +272,43 @@ impl AdventDay for Day06 {
     }
 }
 
+const PALETTE: &Palette = &[
+    ('#', (130, 130, 130)),
+    ('.', (60, 60, 60)),
+    ('X', (230, 170, 40)),
+    ('^', (230, 50, 50)),
+    ('>', (230, 50, 50)),
+    ('v', (230, 50, 50)),
+    ('<', (230, 50, 50)),
+];
+
+impl Visualize for Day06 {
+    /// The guard walking its patrol, leaving a trail of visited cells.
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame>> {
+        let mut map = parse_map(&self.input);
+        let path: Vec<(Position, Direction)> = Guard::new(&map).collect();
+        let mut previous: Option<Position> = None;
+
+        Box::new(
+            path.into_iter()
+                .enumerate()
+                .map(move |(step, (position, direction))| {
+                    if let Some((i, j)) = previous {
+                        map[i][j] = 'X';
+                    }
+                    map[position.0][position.1] = direction.arrow();
+                    previous = Some(position);
+
+                    Frame::new(map.clone(), format!("step {}", step))
+                }),
+        )
+    }
+
+    fn palette(&self) -> &'static Palette {
+        PALETTE
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -181,4 +335,36 @@ mod tests {
         let day06 = Day06::new(DATA.to_string());
         assert_eq!(day06.part_two(), "6");
     }
+
+    #[test]
+    fn jump_table_respects_extra_obstacle() {
+        let map = parse_map(DATA);
+        let table = JumpTable::new(&map);
+
+        assert_eq!(
+            table.next_stop((6, 4), Direction::North, (9, 9)),
+            Some((1, 4))
+        );
+        assert_eq!(
+            table.next_stop((6, 4), Direction::North, (3, 4)),
+            Some((4, 4))
+        );
+        assert_eq!(table.next_stop((6, 4), Direction::South, (9, 9)), None);
+        assert_eq!(
+            table.next_stop((6, 4), Direction::West, (9, 9)),
+            Some((6, 2))
+        );
+    }
+
+    #[test]
+    fn frames_follow_the_guard() {
+        let day06 = Day06::new(DATA.to_string());
+        let frames: Vec<Frame> = day06.frames().collect();
+
+        assert_eq!(frames.len(), 45);
+        assert_eq!(frames[0].tiles[6][4], '^');
+        assert_eq!(frames[5].tiles[1][4], '^');
+        assert_eq!(frames[6].tiles[1][5], '>');
+        assert_eq!(frames[5].tiles[6][4], 'X');
+    }
 }
@@ -1,12 +1,77 @@
-use itertools::Itertools;
+use std::fmt;
 
 use crate::utils::AdventDay;
 
-#[derive(Clone, Copy, Debug)]
-enum Operator {
+/// Operators are evaluated strictly left to right. Operands are positive and
+/// intermediate results never go below zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
     Add,
     Multiply,
     Concatenation,
+    Subtract,
+}
+
+impl Operator {
+    pub fn apply(&self, left: u64, right: u64) -> Option<u64> {
+        match self {
+            Operator::Add => left.checked_add(right),
+            Operator::Multiply => left.checked_mul(right),
+            Operator::Concatenation => concat_shift(right)
+                .and_then(|shift| left.checked_mul(shift))
+                .and_then(|left| left.checked_add(right)),
+            Operator::Subtract => left.checked_sub(right),
+        }
+    }
+
+    /// The left operand that makes `left <op> right == result`, if any.
+    /// This is what prunes the backwards search: a product must be divisible
+    /// by its last factor and a concatenation must end in its last operand.
+    fn undo(&self, result: u64, right: u64) -> Option<Left> {
+        match self {
+            Operator::Add => result.checked_sub(right).map(Left::Exactly),
+            Operator::Multiply if right == 0 => (result == 0).then_some(Left::Any),
+            Operator::Multiply => result
+                .is_multiple_of(right)
+                .then(|| Left::Exactly(result / right)),
+            Operator::Concatenation => concat_shift(right)
+                .filter(|&shift| result % shift == right)
+                .map(|shift| Left::Exactly(result / shift)),
+            Operator::Subtract => result.checked_add(right).map(Left::Exactly),
+        }
+    }
+}
+
+/// The left operand an undone operator needs. Multiplying by zero gives zero
+/// whatever the left operand is.
+#[derive(Debug, PartialEq, Eq)]
+enum Left {
+    Exactly(u64),
+    Any,
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            Operator::Add => "+",
+            Operator::Multiply => "*",
+            Operator::Concatenation => "||",
+            Operator::Subtract => "-",
+        };
+
+        write!(f, "{}", symbol)
+    }
+}
+
+// The power of ten that shifts a number left past all digits of `value`, or
+// `None` if it does not fit in a `u64`.
+fn concat_shift(value: u64) -> Option<u64> {
+    let mut shift: u64 = 10;
+    while shift <= value {
+        shift = shift.checked_mul(10)?;
+    }
+
+    Some(shift)
 }
 
 pub struct Day07 {
@@ -30,31 +95,100 @@ fn parse_input(input: &str) -> Vec<(u64, Vec<u64>)> {
         .collect()
 }
 
-fn is_valid_line(result: u64, values: &[u64], operators: &[Operator]) -> bool {
-    let operator_combinations = (0..values.len() - 1)
-        .map(|_| operators.iter())
-        .multi_cartesian_product();
-
-    for operators in operator_combinations {
-        let mut current_result = values[0];
-        for (i, &value) in values.iter().skip(1).enumerate() {
-            match operators[i] {
-                Operator::Add => current_result += value,
-                Operator::Multiply => current_result *= value,
-                Operator::Concatenation => {
-                    current_result = format!("{}{}", current_result, value).parse().unwrap()
-                }
+/// Finds operators that combine `values` into `result`, working backwards
+/// from the result and the last value so impossible branches are cut early.
+pub fn find_operators(
+    result: u64,
+    values: &[u64],
+    operators: &[Operator],
+) -> Option<Vec<Operator>> {
+    let mut found = Vec::with_capacity(values.len().saturating_sub(1));
+    if solve_backwards(result, values, operators, &mut found) {
+        found.reverse();
+        Some(found)
+    } else {
+        None
+    }
+}
+
+fn solve_backwards(
+    result: u64,
+    values: &[u64],
+    operators: &[Operator],
+    found: &mut Vec<Operator>,
+) -> bool {
+    let Some((&last, rest)) = values.split_last() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return last == result;
+    }
+
+    for &operator in operators {
+        let Some(left) = operator.undo(result, last) else {
+            continue;
+        };
+
+        found.push(operator);
+        let solved = match left {
+            Left::Exactly(left) => solve_backwards(left, rest, operators, found),
+            Left::Any => {
+                let mut forwards = Vec::new();
+                let solved = solve_forwards(rest[0], &rest[1..], operators, &mut forwards);
+                found.extend(forwards.into_iter().rev());
+                solved
             }
+        };
+        if solved {
+            return true;
         }
+        found.pop();
+    }
+
+    false
+}
+
+/// Finds any operators that combine `acc` and `values` without overflowing,
+/// for when the result does not matter.
+fn solve_forwards(
+    acc: u64,
+    values: &[u64],
+    operators: &[Operator],
+    found: &mut Vec<Operator>,
+) -> bool {
+    let Some((&next, rest)) = values.split_first() else {
+        return true;
+    };
 
-        if current_result == result {
+    for &operator in operators {
+        let Some(acc) = operator.apply(acc, next) else {
+            continue;
+        };
+
+        found.push(operator);
+        if solve_forwards(acc, rest, operators, found) {
             return true;
         }
+        found.pop();
     }
 
     false
 }
 
+/// Renders an equation like `3267 = 81 + 40 * 27`.
+pub fn format_equation(result: u64, values: &[u64], operators: &[Operator]) -> String {
+    let mut equation = format!("{} = {}", result, values[0]);
+    for (operator, value) in operators.iter().zip(&values[1..]) {
+        equation.push_str(&format!(" {} {}", operator, value));
+    }
+
+    equation
+}
+
+fn is_valid_line(result: u64, values: &[u64], operators: &[Operator]) -> bool {
+    find_operators(result, values, operators).is_some()
+}
+
 fn sum_valid_lines(data: &[(u64, Vec<u64>)], operators: &[Operator]) -> u64 {
     data.iter()
         .filter(|(result, values)| is_valid_line(*result, values, operators))
@@ -62,6 +196,19 @@ fn sum_valid_lines(data: &[(u64, Vec<u64>)], operators: &[Operator]) -> u64 {
         .sum()
 }
 
+impl Day07 {
+    /// Every solvable equation with the operators that solve it.
+    pub fn solutions(&self, operators: &[Operator]) -> Vec<String> {
+        parse_input(&self.input)
+            .iter()
+            .filter_map(|(result, values)| {
+                find_operators(*result, values, operators)
+                    .map(|found| format_equation(*result, values, &found))
+            })
+            .collect()
+    }
+}
+
 impl AdventDay for Day07 {
     fn new(input: String) -> Self {
         Self { input }
@@ -105,4 +252,69 @@ mod tests {
         let day07 = Day07::new(DATA.to_string());
         assert_eq!(day07.part_two(), "11387");
     }
+
+    #[test]
+    fn find_operators_reconstructs_equation() {
+        let operators = [Operator::Add, Operator::Multiply, Operator::Concatenation];
+        let found = find_operators(7290, &[6, 8, 6, 15], &operators).unwrap();
+
+        assert_eq!(
+            format_equation(7290, &[6, 8, 6, 15], &found),
+            "7290 = 6 * 8 || 6 * 15"
+        );
+        let evaluated = found
+            .iter()
+            .zip(&[8, 6, 15])
+            .try_fold(6, |left, (operator, &right)| operator.apply(left, right));
+        assert_eq!(evaluated, Some(7290));
+        assert_eq!(find_operators(83, &[17, 5], &operators), None);
+    }
+
+    #[test]
+    fn find_operators_with_subtraction() {
+        let operators = [Operator::Add, Operator::Subtract];
+        let found = find_operators(4, &[10, 3, 3], &operators).unwrap();
+
+        assert_eq!(found, [Operator::Subtract, Operator::Subtract]);
+        assert_eq!(Operator::Subtract.apply(3, 10), None);
+    }
+
+    #[test]
+    fn find_operators_times_zero() {
+        let operators = [Operator::Add, Operator::Multiply];
+
+        assert_eq!(
+            find_operators(0, &[5, 3, 0], &operators),
+            Some(vec![Operator::Add, Operator::Multiply])
+        );
+        assert_eq!(
+            find_operators(0, &[5, 0, 2], &operators),
+            Some(vec![Operator::Multiply, Operator::Multiply])
+        );
+        assert_eq!(find_operators(1, &[5, 0], &operators), None);
+    }
+
+    #[test]
+    fn concatenation_overflow_is_impossible() {
+        let big = 10_000_000_000_000_000_000;
+
+        assert_eq!(Operator::Concatenation.apply(1, big), None);
+        assert_eq!(Operator::Concatenation.undo(u64::MAX, big), None);
+        assert_eq!(
+            find_operators(u64::MAX, &[1, big], &[Operator::Concatenation]),
+            None
+        );
+    }
+
+    #[test]
+    fn solutions_list_operators() {
+        assert_eq!(
+            Day07::new(DATA.to_string()).solutions(&[Operator::Add, Operator::Multiply]),
+            [
+                "190 = 10 * 19",
+                "3267 = 81 * 40 + 27",
+                "292 = 11 + 6 * 16 + 20"
+            ]
+        );
+    }
 }
@@ -1,5 +1,8 @@
 use crate::utils::AdventDay;
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::RangeInclusive,
+};
 
 fn collect_antennas(map: &[Vec<char>]) -> Vec<(usize, usize, char)> {
     let mut antennas = Vec::new();
@@ -15,10 +18,16 @@ fn collect_antennas(map: &[Vec<char>]) -> Vec<(usize, usize, char)> {
     antennas
 }
 
+/// Collect the antinodes of every pair of antennas with the same frequency.
+/// For antennas `a` and `b`, the `k`th harmonic lies at `a - k * (b - a)`,
+/// so harmonic 0 is the antenna itself and harmonic 1 is its reflection
+/// through the other antenna. Only harmonics within `harmonics` are kept,
+/// and the walk stops at the edge of the map.
 fn collect_antinodes(
-    antennas: &Vec<(usize, usize, char)>,
+    antennas: &[(usize, usize, char)],
     width: usize,
     height: usize,
+    harmonics: RangeInclusive<usize>,
 ) -> HashSet<(usize, usize)> {
     let mut antinodes = HashSet::new();
     let mut groups: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
@@ -28,45 +37,23 @@ fn collect_antinodes(
     }
 
     for positions in groups.values() {
-        let len = positions.len();
-        for i in 0..len {
-            let (x1, y1) = positions[i];
-
-            for (j, value) in positions.iter().enumerate().take(len) {
+        for (i, &(x1, y1)) in positions.iter().enumerate() {
+            for (j, &(x2, y2)) in positions.iter().enumerate() {
                 if i == j {
                     continue;
                 }
 
-                let (x2, y2) = *value;
-
                 let dx = x2 as isize - x1 as isize;
                 let dy = y2 as isize - y1 as isize;
 
-                antinodes.insert((x1, y1));
-                antinodes.insert((x2, y2));
-
-                let mut x_prev = x1 as isize;
-                let mut y_prev = y1 as isize;
-                while x_prev >= 0
-                    && y_prev >= 0
-                    && x_prev < width as isize
-                    && y_prev < height as isize
-                {
-                    antinodes.insert((x_prev as usize, y_prev as usize));
-                    x_prev -= dx;
-                    y_prev -= dy;
-                }
+                for k in harmonics.clone() {
+                    let x = x1 as isize - k as isize * dx;
+                    let y = y1 as isize - k as isize * dy;
+                    if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+                        break;
+                    }
 
-                let mut x_next = x1 as isize;
-                let mut y_next = y1 as isize;
-                while x_next >= 0
-                    && y_next >= 0
-                    && x_next < width as isize
-                    && y_next < height as isize
-                {
-                    antinodes.insert((x_next as usize, y_next as usize));
-                    x_next += dx;
-                    y_next += dy;
+                    antinodes.insert((x as usize, y as usize));
                 }
             }
         }
@@ -75,31 +62,6 @@ fn collect_antinodes(
     antinodes
 }
 
-fn is_valid_antinode(antennas: &Vec<(usize, usize, char)>, x: usize, y: usize) -> bool {
-    for &(x1, y1, freq1) in antennas {
-        for &(x2, y2, freq2) in antennas {
-            if freq1 == freq2 && (x1, y1) != (x2, y2) {
-                let dx1 = x as isize - x1 as isize;
-                let dy1 = y as isize - y1 as isize;
-                let dx2 = x2 as isize - x as isize;
-                let dy2 = y2 as isize - y as isize;
-                let is_colinear = dx1 * dy2 == dy1 * dx2;
-
-                if is_colinear {
-                    let dist1 = dx1.abs() + dy1.abs();
-                    let dist2 = dx2.abs() + dy2.abs();
-
-                    if dist1 == 2 * dist2 || dist2 == 2 * dist1 {
-                        return true;
-                    }
-                }
-            }
-        }
-    }
-
-    false
-}
-
 fn get_map(input: &str) -> Vec<Vec<char>> {
     input.lines().map(|line| line.chars().collect()).collect()
 }
@@ -118,14 +80,9 @@ impl AdventDay for Day08 {
         let (height, width) = (map.len(), map[0].len());
 
         let antennas = collect_antennas(&map);
-        let antinodes = collect_antinodes(&antennas, width, height);
-
-        let valid_antinodes: HashSet<(usize, usize)> = antinodes
-            .into_iter()
-            .filter(|&(x, y)| is_valid_antinode(&antennas, x, y))
-            .collect();
+        let antinodes = collect_antinodes(&antennas, width, height, 1..=1);
 
-        valid_antinodes.len().to_string()
+        antinodes.len().to_string()
     }
 
     fn part_two(&self) -> String {
@@ -133,7 +90,7 @@ impl AdventDay for Day08 {
         let (height, width) = (map.len(), map[0].len());
 
         let antennas = collect_antennas(&map);
-        let antinodes = collect_antinodes(&antennas, width, height);
+        let antinodes = collect_antinodes(&antennas, width, height, 0..=usize::MAX);
 
         antinodes.len().to_string()
     }
@@ -167,4 +124,15 @@ mod tests {
         let day08 = Day08::new(DATA.to_string());
         assert_eq!(day08.part_two(), "34");
     }
+
+    #[test]
+    fn collect_antinodes_by_harmonic() {
+        let antennas = [(4, 3, 'a'), (5, 5, 'a')];
+
+        let first = collect_antinodes(&antennas, 10, 10, 1..=1);
+        assert_eq!(first, HashSet::from([(3, 1), (6, 7)]));
+
+        let second = collect_antinodes(&antennas, 10, 10, 2..=2);
+        assert_eq!(second, HashSet::from([(7, 9)]));
+    }
 }
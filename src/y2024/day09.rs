@@ -1,135 +1,128 @@
 use crate::utils::AdventDay;
-use std::collections::HashMap;
+use std::{cmp::Reverse, collections::BinaryHeap};
 
 // (taken, free)
-type DiskMap = Vec<(u32, u32)>;
-
-type ExpandedDiskMap = Vec<Option<usize>>;
-
-// Replace all empty spaces with the right-most non-empty space.
-fn stabilize(disk_map: &ExpandedDiskMap) -> ExpandedDiskMap {
-    let mut stabilized = disk_map.to_vec();
-
-    for i in 0..stabilized.len() {
-        if stabilized[i].is_none() {
-            if let Some(to_swap) = stabilized.iter().rposition(|v| v.is_some()) {
-                stabilized.swap(i, to_swap);
-            }
-        }
-    }
-
-    stabilized.iter().filter(|v| v.is_some()).cloned().collect()
+type DiskMap = Vec<(usize, usize)>;
+
+/// A contiguous run of blocks belonging to one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Segment {
+    id: usize,
+    start: usize,
+    len: usize,
 }
 
-fn find_leftmost_suitable_run(
-    end_index: usize,
-    length_needed: usize,
-    map: &ExpandedDiskMap,
-) -> Option<usize> {
-    let mut runs = vec![];
-    let mut run_start = None;
-    let mut run_length = 0;
-
-    for (idx, value) in map.iter().enumerate().take(end_index) {
-        if value.is_none() {
-            if run_start.is_none() {
-                run_start = Some(idx);
-                run_length = 1;
-            } else {
-                run_length += 1;
-            }
-        } else {
-            if let Some(start) = run_start {
-                runs.push((start, run_length));
-            }
-            run_start = None;
-            run_length = 0;
-        }
+impl Segment {
+    // id * (start + (start + 1) + ... + (start + len - 1))
+    fn checksum(&self) -> usize {
+        self.id * (self.len * self.start + self.len * self.len.saturating_sub(1) / 2)
     }
-    if let Some(start) = run_start {
-        runs.push((start, run_length));
-    }
-
-    runs.iter()
-        .filter(|(_, length)| *length >= length_needed)
-        .min_by_key(|(start, _)| *start)
-        .map(|(s, _)| *s)
 }
 
-fn rearrange_disk_map(disk_map: &ExpandedDiskMap) -> ExpandedDiskMap {
-    let mut rearranged = disk_map.clone();
-
-    let mut file_positions: HashMap<usize, (usize, usize)> = HashMap::new();
-    for (i, &val) in rearranged.iter().enumerate() {
-        if let Some(fid) = val {
-            file_positions
-                .entry(fid)
-                .and_modify(|(_start, count)| *count += 1)
-                .or_insert((i, 1));
+// Move single blocks from the end of the disk into the leftmost gaps.
+// A left and a right pointer walk towards each other, so every file and gap
+// is visited once.
+fn compact_blocks(disk_map: &DiskMap) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut remaining: Vec<usize> = disk_map.iter().map(|(taken, _)| *taken).collect();
+    let mut position = 0;
+
+    let (mut left, mut right) = (0, disk_map.len().saturating_sub(1));
+
+    while left <= right && left < disk_map.len() {
+        if remaining[left] > 0 {
+            segments.push(Segment {
+                id: left,
+                start: position,
+                len: remaining[left],
+            });
+            position += remaining[left];
+            remaining[left] = 0;
         }
-    }
 
-    let mut files_vec: Vec<(usize, usize, usize)> = file_positions
-        .into_iter()
-        .map(|(fid, (start, count))| (fid, start, count))
-        .collect();
-    files_vec.sort_by_key(|x| x.0);
-    files_vec.reverse();
-
-    for &(fid, _fstart, fcount) in &files_vec {
-        let file_positions: Vec<usize> = rearranged
-            .iter()
-            .enumerate()
-            .filter_map(|(i, &v)| if v == Some(fid) { Some(i) } else { None })
-            .collect();
-        let current_start = *file_positions.first().unwrap();
-
-        if let Some(target_start) = find_leftmost_suitable_run(current_start, fcount, &rearranged) {
-            for &pos in &file_positions {
-                rearranged[pos] = None;
+        let mut free = disk_map[left].1;
+        while free > 0 && right > left {
+            if remaining[right] == 0 {
+                right -= 1;
+                continue;
             }
 
-            for i in 0..fcount {
-                rearranged[target_start + i] = Some(fid);
-            }
+            let moved = free.min(remaining[right]);
+            segments.push(Segment {
+                id: right,
+                start: position,
+                len: moved,
+            });
+            position += moved;
+            free -= moved;
+            remaining[right] -= moved;
         }
+
+        left += 1;
     }
 
-    rearranged
+    segments
 }
 
-fn expand_disk_map(disk_map: &DiskMap) -> ExpandedDiskMap {
-    let mut expanded: Vec<Option<usize>> = Vec::new();
-
-    for (id, (taken, free)) in disk_map.iter().enumerate() {
-        for _ in 0..*taken {
-            expanded.push(Some(id));
-        }
-
-        for _ in 0..*free {
-            expanded.push(None);
+// Move whole files, highest id first, into the leftmost gap that fits them.
+// Gaps are kept in one min-heap of start positions per gap size, so finding
+// the leftmost fitting gap only looks at the top of ten heaps.
+fn compact_files(disk_map: &DiskMap) -> Vec<Segment> {
+    let mut files = Vec::with_capacity(disk_map.len());
+    let mut gaps: Vec<BinaryHeap<Reverse<usize>>> = vec![BinaryHeap::new(); 10];
+    let mut position = 0;
+
+    for (id, &(taken, free)) in disk_map.iter().enumerate() {
+        files.push(Segment {
+            id,
+            start: position,
+            len: taken,
+        });
+        position += taken;
+
+        if free > 0 {
+            gaps[free].push(Reverse(position));
         }
+        position += free;
     }
 
-    expanded
-}
+    for file in files.iter_mut().rev() {
+        let leftmost_gap = (file.len..gaps.len())
+            .filter_map(|size| gaps[size].peek().map(|Reverse(start)| (*start, size)))
+            .min();
+
+        let Some((start, size)) = leftmost_gap else {
+            continue;
+        };
+        if start >= file.start {
+            continue;
+        }
 
-fn calculate_checksum(disk_map: &ExpandedDiskMap) -> usize {
-    let mut checksum = 0;
+        gaps[size].pop();
+        file.start = start;
 
-    for (i, value) in disk_map.iter().enumerate() {
-        if let Some(value) = value {
-            checksum += i * value;
+        // Space freed by the file is never used, since every file left to
+        // move sits further left.
+        if size > file.len {
+            gaps[size - file.len].push(Reverse(start + file.len));
         }
     }
 
-    checksum
+    files
+}
+
+fn calculate_checksum(segments: &[Segment]) -> usize {
+    segments.iter().map(Segment::checksum).sum()
 }
 
 // Parse the input data into a vector of tuples.
 // The first element represents taken space, the second represents free space.
 fn collect_disk_map(data: &str) -> DiskMap {
-    let digits: Vec<u32> = data.chars().map(|c| c.to_digit(10).unwrap()).collect();
+    let digits: Vec<usize> = data
+        .trim()
+        .chars()
+        .map(|c| c.to_digit(10).unwrap() as usize)
+        .collect();
     digits
         .chunks(2)
         .map(|chunk| (*chunk.first().unwrap_or(&0), *chunk.get(1).unwrap_or(&0)))
@@ -147,17 +140,15 @@ impl AdventDay for Day09 {
 
     fn part_one(&self) -> String {
         let disk_map = collect_disk_map(&self.input);
-        let expanded_disk_map = expand_disk_map(&disk_map);
-        let stabilized_disk_map = stabilize(&expanded_disk_map);
-        let checksum = calculate_checksum(&stabilized_disk_map);
+        let segments = compact_blocks(&disk_map);
+        let checksum = calculate_checksum(&segments);
         checksum.to_string()
     }
 
     fn part_two(&self) -> String {
         let disk_map = collect_disk_map(&self.input);
-        let expanded_disk_map = expand_disk_map(&disk_map);
-        let rearranged_disk_map = rearrange_disk_map(&expanded_disk_map);
-        let checksum = calculate_checksum(&rearranged_disk_map);
+        let segments = compact_files(&disk_map);
+        let checksum = calculate_checksum(&segments);
         checksum.to_string()
     }
 }
@@ -168,6 +159,18 @@ mod tests {
 
     const DATA: &str = "2333133121414131402";
 
+    fn render(disk_map: &DiskMap, segments: &[Segment]) -> String {
+        let size = disk_map.iter().map(|(taken, free)| taken + free).sum();
+        let mut blocks = vec![".".to_string(); size];
+        for segment in segments {
+            for block in &mut blocks[segment.start..segment.start + segment.len] {
+                *block = segment.id.to_string();
+            }
+        }
+
+        blocks.join("")
+    }
+
     #[test]
     fn part_one() {
         let day09 = Day09::new(DATA.to_string());
@@ -180,20 +183,23 @@ mod tests {
         assert_eq!(day09.part_two(), "2858");
     }
 
+    #[test]
+    fn compact_blocks_test() {
+        let disk_map = collect_disk_map(DATA);
+        let segments = compact_blocks(&disk_map);
+
+        let actual = render(&disk_map, &segments);
+        let expected = "0099811188827773336446555566..............";
+
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn rearrange_disk_map_test() {
         let disk_map = collect_disk_map(DATA);
-        let expanded_disk_map = expand_disk_map(&disk_map);
-        let rearranged_disk_map = rearrange_disk_map(&expanded_disk_map);
-
-        let actual: String = rearranged_disk_map
-            .iter()
-            .map(|v| match v {
-                Some(v) => v.to_string(),
-                None => ".".to_string(),
-            })
-            .collect::<Vec<String>>()
-            .join("");
+        let segments = compact_files(&disk_map);
+
+        let actual = render(&disk_map, &segments);
         let expected = "00992111777.44.333....5555.6666.....8888..";
 
         assert_eq!(actual, expected);
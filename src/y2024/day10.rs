@@ -1,113 +1,217 @@
+use crate::utils::svg::{Draw, Svg};
 use crate::utils::AdventDay;
-use std::collections::{HashMap, HashSet};
-use std::hash::Hash;
+use std::collections::{BTreeSet, HashSet};
 
-type Map = HashMap<(u32, u32), u32>;
+type Position = (usize, usize);
+
+/// Height of every cell, `None` for impassable cells such as `.`
+type Map = Vec<Vec<Option<u32>>>;
+
+const TRAILHEAD_HEIGHT: u32 = 0;
+const SUMMIT_HEIGHT: u32 = 9;
 
 fn collect_map(input: &str) -> Map {
     input
         .lines()
-        .enumerate()
-        .flat_map(|(i, line)| {
-            line.chars().enumerate().map(move |(j, c)| {
-                let height = c.to_digit(10).unwrap();
-                ((i as u32, j as u32), height)
-            })
-        })
+        .map(|line| line.chars().map(|c| c.to_digit(10)).collect())
         .collect()
 }
 
-fn collect_trailheads(map: &Map) -> HashSet<(u32, u32)> {
-    map.iter()
-        .filter_map(
-            |(&(i, j), height)| {
-                if *height == 0 {
-                    Some((i, j))
-                } else {
-                    None
-                }
-            },
-        )
-        .collect()
+fn neighbors(map: &Map, (i, j): Position) -> impl Iterator<Item = Position> + '_ {
+    [
+        (i.wrapping_sub(1), j),
+        (i.wrapping_add(1), j),
+        (i, j.wrapping_sub(1)),
+        (i, j.wrapping_add(1)),
+    ]
+    .into_iter()
+    .filter(|&(ni, nj)| ni < map.len() && nj < map[ni].len())
 }
 
-fn find_summits(map: &Map, trailhead: (u32, u32)) -> Vec<(u32, u32)> {
-    let mut stack = vec![(trailhead, Vec::new())];
-    let mut summits = Vec::new();
+/// For every cell, the summits reachable from it and the number of distinct
+/// trails leading to them.
+struct Trails {
+    summits: Vec<Vec<HashSet<Position>>>,
+    counts: Vec<Vec<usize>>,
+}
 
-    while let Some(((i, j), path)) = stack.pop() {
-        let height = *map.get(&(i, j)).unwrap();
+/// Walk the heights from the summit down to the trailhead height, so every
+/// cell only has to combine the results of its neighbours one step higher.
+fn analyse_trails(map: &Map, start: u32, end: u32) -> Trails {
+    let mut summits: Vec<Vec<HashSet<Position>>> = map
+        .iter()
+        .map(|row| vec![HashSet::new(); row.len()])
+        .collect();
+    let mut counts: Vec<Vec<usize>> = map.iter().map(|row| vec![0; row.len()]).collect();
 
-        if height == 9 {
-            summits.push((i, j));
-            continue;
+    let mut by_height: Vec<Vec<Position>> = vec![Vec::new(); (end - start + 1) as usize];
+    for (i, row) in map.iter().enumerate() {
+        for (j, height) in row.iter().enumerate() {
+            if let Some(height) = height.filter(|height| (start..=end).contains(height)) {
+                by_height[(height - start) as usize].push((i, j));
+            }
         }
+    }
+
+    for &(i, j) in &by_height[(end - start) as usize] {
+        summits[i][j].insert((i, j));
+        counts[i][j] = 1;
+    }
 
-        let neighbors = [
-            (i.wrapping_sub(1), j),
-            (i.wrapping_add(1), j),
-            (i, j.wrapping_sub(1)),
-            (i, j.wrapping_add(1)),
-        ];
-
-        for &(ni, nj) in &neighbors {
-            if let Some(&neighbor_height) = map.get(&(ni, nj)) {
-                if neighbor_height == height + 1 {
-                    let mut new_path = path.clone();
-                    new_path.push((i, j));
-                    stack.push(((ni, nj), new_path));
+    for height in (start..end).rev() {
+        for &(i, j) in &by_height[(height - start) as usize] {
+            for (ni, nj) in neighbors(map, (i, j)) {
+                if map[ni][nj] != Some(height + 1) {
+                    continue;
                 }
+
+                let reachable = summits[ni][nj].clone();
+                summits[i][j].extend(reachable);
+                counts[i][j] += counts[ni][nj];
             }
         }
     }
 
-    summits
+    Trails { summits, counts }
+}
+
+/// List every trail from `trailhead`, only following cells that still lead
+/// to a summit.
+fn list_trails(map: &Map, trails: &Trails, trailhead: Position, end: u32) -> Vec<Vec<Position>> {
+    let mut found = Vec::new();
+    let mut path = vec![trailhead];
+    extend_trail(map, trails, end, &mut path, &mut found);
+
+    found
 }
 
-fn to_set<T: Eq + Hash + Clone>(vec: Vec<T>) -> Vec<T> {
-    let mut seen = HashSet::new();
-    vec.into_iter()
-        .filter(|item| seen.insert(item.clone()))
+fn extend_trail(
+    map: &Map,
+    trails: &Trails,
+    end: u32,
+    path: &mut Vec<Position>,
+    found: &mut Vec<Vec<Position>>,
+) {
+    let (i, j) = *path.last().unwrap();
+    let height = map[i][j].unwrap();
+
+    if height == end {
+        found.push(path.clone());
+        return;
+    }
+
+    for (ni, nj) in neighbors(map, (i, j)) {
+        if map[ni][nj] == Some(height + 1) && trails.counts[ni][nj] > 0 {
+            path.push((ni, nj));
+            extend_trail(map, trails, end, path, found);
+            path.pop();
+        }
+    }
+}
+
+fn collect_trailheads(map: &Map, start: u32) -> Vec<Position> {
+    map.iter()
+        .enumerate()
+        .flat_map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .filter(move |(_, height)| **height == Some(start))
+                .map(move |(j, _)| (i, j))
+        })
         .collect()
 }
 
 pub struct Day10 {
     input: String,
+    start_height: u32,
+    end_height: u32,
+}
+
+impl Day10 {
+    pub fn with_heights(input: String, start_height: u32, end_height: u32) -> Self {
+        assert!(start_height <= end_height, "trails must lead upwards");
+
+        Self {
+            input,
+            start_height,
+            end_height,
+        }
+    }
+
+    /// Every trail from every trailhead, as the cells along it.
+    pub fn trails(&self) -> Vec<Vec<Position>> {
+        let map = collect_map(&self.input);
+        let trails = analyse_trails(&map, self.start_height, self.end_height);
+
+        collect_trailheads(&map, self.start_height)
+            .into_iter()
+            .flat_map(|trailhead| list_trails(&map, &trails, trailhead, self.end_height))
+            .collect()
+    }
 }
 
 impl AdventDay for Day10 {
     fn new(input: String) -> Self {
-        Self { input }
+        Self::with_heights(input, TRAILHEAD_HEIGHT, SUMMIT_HEIGHT)
     }
 
     fn part_one(&self) -> String {
         let map = collect_map(&self.input);
-        let trailheads = collect_trailheads(&map);
-        let mut sum = 0;
+        let trails = analyse_trails(&map, self.start_height, self.end_height);
 
-        for trailhead in trailheads {
-            let summits = find_summits(&map, trailhead);
-            if !summits.is_empty() {
-                sum += to_set(summits).len();
-            }
-        }
-
-        sum.to_string()
+        collect_trailheads(&map, self.start_height)
+            .iter()
+            .map(|&(i, j)| trails.summits[i][j].len())
+            .sum::<usize>()
+            .to_string()
     }
 
     fn part_two(&self) -> String {
         let map = collect_map(&self.input);
-        let trailheads = collect_trailheads(&map);
-        let mut sum = 0;
+        let trails = analyse_trails(&map, self.start_height, self.end_height);
 
-        for trailhead in trailheads {
-            let summits = find_summits(&map, trailhead);
-            if !summits.is_empty() {
-                sum += summits.len();
-            }
+        collect_trailheads(&map, self.start_height)
+            .iter()
+            .map(|&(i, j)| trails.counts[i][j])
+            .sum::<usize>()
+            .to_string()
+    }
+}
+
+impl Draw for Day10 {
+    /// The heights as shades of grey, brighter going up, with every trail
+    /// drawn over them and its trailhead and summit marked.
+    fn svg(&self) -> Svg {
+        let map = collect_map(&self.input);
+        let mut svg = Svg::new(map.len(), map.first().map_or(0, Vec::len), 16);
+
+        for height in 0..=9 {
+            let cells = map.iter().enumerate().flat_map(|(i, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(move |&(_, &h)| h == Some(height))
+                    .map(move |(j, _)| (i, j))
+            });
+            let shade = 30 + height as u8 * 20;
+            svg.cells(cells, (shade, shade, shade), 1.0);
+        }
+
+        let trails = self.trails();
+        for trail in &trails {
+            svg.path(trail, (90, 200, 110), 3.0, 0.4);
         }
 
-        sum.to_string()
+        let trailheads: BTreeSet<Position> = trails.iter().map(|trail| trail[0]).collect();
+        let summits: BTreeSet<Position> =
+            trails.iter().map(|trail| trail[trail.len() - 1]).collect();
+        for &trailhead in &trailheads {
+            svg.marker(trailhead, (60, 200, 80));
+        }
+        for &summit in &summits {
+            svg.marker(summit, (230, 70, 60));
+        }
+
+        svg
     }
 }
 
@@ -135,4 +239,40 @@ mod tests {
         let day10 = Day10::new(DATA.to_string());
         assert_eq!(day10.part_two(), "81");
     }
+
+    #[test]
+    fn configurable_heights() {
+        let day10 = Day10::with_heights(DATA.to_string(), 8, 9);
+        assert_eq!(day10.part_two(), "11");
+    }
+
+    #[test]
+    fn trails_match_rating() {
+        let day10 = Day10::new(
+            r#"..90..9
+...1.98
+...2..7
+6543456
+765.987
+876....
+987...."#
+                .to_string(),
+        );
+
+        let trails = day10.trails();
+        assert_eq!(day10.part_two(), "13");
+        assert_eq!(trails.len(), 13);
+        assert!(trails
+            .iter()
+            .all(|trail| trail.len() == 10 && trail[0] == (0, 3)));
+    }
+
+    #[test]
+    fn svg_draws_every_trail() {
+        let day10 = Day10::new(DATA.to_string());
+        let svg = day10.svg().to_string();
+
+        assert_eq!(svg.matches("<polyline").count(), 81);
+        assert_eq!(svg.matches("<circle").count(), 9 + 7);
+    }
 }
@@ -8,7 +8,7 @@ enum BlinkResult {
 }
 
 fn has_even_digits(n: usize) -> bool {
-    n.to_string().len() % 2 == 0
+    n.to_string().len().is_multiple_of(2)
 }
 
 fn split_number(n: usize) -> (usize, usize) {
@@ -30,50 +30,79 @@ fn blink(stone: usize) -> BlinkResult {
     }
 }
 
-fn blink_cached(stone: usize, memo: &mut HashMap<usize, BlinkResult>) -> BlinkResult {
-    if let Some(&cached) = memo.get(&stone) {
-        cached
-    } else {
-        let result = blink(stone);
-        memo.insert(stone, result);
-        result
-    }
+/// Number of stones per engraved value. Stones with the same value always
+/// evolve the same way, so their order on the line can be ignored.
+pub type Distribution = HashMap<usize, usize>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlinkStats {
+    pub blinks: usize,
+    pub total: usize,
+    pub distinct: usize,
+    pub largest: usize,
 }
 
-fn blink_multiple_one_stone(
-    stone: usize,
-    n: usize,
-    count_memo: &mut HashMap<(usize, usize), usize>,
-    result_memo: &mut HashMap<usize, BlinkResult>,
-) -> usize {
-    if n == 0 {
-        return 1;
-    }
+/// Blinks a line of stones as a frequency map. The result of blinking each
+/// value is remembered for the lifetime of the simulation.
+pub struct Simulation {
+    distribution: Distribution,
+    blinks: usize,
+    memo: HashMap<usize, BlinkResult>,
+}
 
-    if let Some(&count) = count_memo.get(&(stone, n)) {
-        return count;
+impl Simulation {
+    pub fn new(stones: &[usize]) -> Self {
+        let mut distribution = Distribution::new();
+        for &stone in stones {
+            *distribution.entry(stone).or_default() += 1;
+        }
+
+        Self {
+            distribution,
+            blinks: 0,
+            memo: HashMap::new(),
+        }
     }
 
-    let result = blink_cached(stone, result_memo);
-    let count = match result {
-        BlinkResult::One(s) => blink_multiple_one_stone(s, n - 1, count_memo, result_memo),
-        BlinkResult::Split(left, right) => {
-            blink_multiple_one_stone(left, n - 1, count_memo, result_memo)
-                + blink_multiple_one_stone(right, n - 1, count_memo, result_memo)
+    pub fn blink(&mut self) {
+        let mut next = Distribution::with_capacity(self.distribution.len());
+
+        for (&stone, &count) in &self.distribution {
+            let result = *self.memo.entry(stone).or_insert_with(|| blink(stone));
+            match result {
+                BlinkResult::One(s) => *next.entry(s).or_default() += count,
+                BlinkResult::Split(left, right) => {
+                    *next.entry(left).or_default() += count;
+                    *next.entry(right).or_default() += count;
+                }
+            }
         }
-    };
 
-    count_memo.insert((stone, n), count);
-    count
+        self.distribution = next;
+        self.blinks += 1;
+    }
+
+    pub fn distribution(&self) -> &Distribution {
+        &self.distribution
+    }
+
+    pub fn stats(&self) -> BlinkStats {
+        BlinkStats {
+            blinks: self.blinks,
+            total: self.distribution.values().sum(),
+            distinct: self.distribution.len(),
+            largest: self.distribution.keys().copied().max().unwrap_or(0),
+        }
+    }
 }
 
 fn blink_multiple(stones: &[usize], n: usize) -> usize {
-    let mut result_memo = HashMap::new();
-    let mut count_memo = HashMap::new();
-    stones
-        .iter()
-        .map(|&s| blink_multiple_one_stone(s, n, &mut count_memo, &mut result_memo))
-        .sum()
+    let mut simulation = Simulation::new(stones);
+    for _ in 0..n {
+        simulation.blink();
+    }
+
+    simulation.stats().total
 }
 
 fn collect_stones(input: &str) -> Vec<usize> {
@@ -83,10 +112,29 @@ fn collect_stones(input: &str) -> Vec<usize> {
         .collect()
 }
 
+const PART_ONE_BLINKS: usize = 25;
+const PART_TWO_BLINKS: usize = 75;
+
 pub struct Day11 {
     input: String,
 }
 
+impl Day11 {
+    /// Statistics of the stone line before the first blink and after each
+    /// of the following `blinks` blinks.
+    pub fn simulate(&self, blinks: usize) -> Vec<BlinkStats> {
+        let mut simulation = Simulation::new(&collect_stones(&self.input));
+        let mut history = vec![simulation.stats()];
+
+        for _ in 0..blinks {
+            simulation.blink();
+            history.push(simulation.stats());
+        }
+
+        history
+    }
+}
+
 impl AdventDay for Day11 {
     fn new(input: String) -> Self {
         Self { input }
@@ -94,12 +142,12 @@ impl AdventDay for Day11 {
 
     fn part_one(&self) -> String {
         let stones = collect_stones(&self.input);
-        blink_multiple(&stones, 25).to_string()
+        blink_multiple(&stones, PART_ONE_BLINKS).to_string()
     }
 
     fn part_two(&self) -> String {
         let stones = collect_stones(&self.input);
-        blink_multiple(&stones, 75).to_string()
+        blink_multiple(&stones, PART_TWO_BLINKS).to_string()
     }
 }
 
@@ -114,4 +162,33 @@ mod tests {
         let day11 = Day11::new(DATA.to_string());
         assert_eq!(day11.part_one(), "55312");
     }
+
+    #[test]
+    fn simulation_distribution() {
+        let mut simulation = Simulation::new(&collect_stones(DATA));
+        for _ in 0..6 {
+            simulation.blink();
+        }
+
+        assert_eq!(simulation.distribution()[&2], 4);
+        assert_eq!(
+            simulation.stats(),
+            BlinkStats {
+                blinks: 6,
+                total: 22,
+                distinct: 15,
+                largest: 2097446912,
+            }
+        );
+    }
+
+    #[test]
+    fn simulate_history() {
+        let day11 = Day11::new(DATA.to_string());
+        let history = day11.simulate(25);
+
+        assert_eq!(history.len(), 26);
+        assert_eq!(history[0].total, 2);
+        assert_eq!(history[25].total, 55312);
+    }
 }
@@ -1,40 +1,41 @@
+use crate::utils::svg::{Cell, Draw, Svg};
 use crate::utils::AdventDay;
-use std::collections::HashSet;
 
-/// Get the perimeter of a region
-fn calculate_perimeter(region: HashSet<(usize, usize)>) -> usize {
-    let mut perimeter = 0;
-
-    for (i, j) in region.iter() {
-        if !region.contains(&(i.wrapping_add(1), *j)) {
-            perimeter += 1;
-        }
-
-        if !region.contains(&(i.wrapping_sub(1), *j)) {
-            perimeter += 1;
-        }
-
-        if !region.contains(&(*i, j.wrapping_add(1))) {
-            perimeter += 1;
-        }
+/// Region index of every plot in the map.
+type Labels = Vec<Vec<usize>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Region {
+    plant: char,
+    area: usize,
+    perimeter: usize,
+    sides: usize,
+    /// Top-left and bottom-right plot, inclusive
+    bounds: ((usize, usize), (usize, usize)),
+}
 
-        if !region.contains(&(*i, j.wrapping_sub(1))) {
-            perimeter += 1;
-        }
+fn label_at(labels: &Labels, i: isize, j: isize) -> Option<usize> {
+    if i < 0 || j < 0 {
+        return None;
     }
 
-    perimeter
+    labels.get(i as usize)?.get(j as usize).copied()
 }
 
-fn cell_in_region(region: &HashSet<(usize, usize)>, i: isize, j: isize) -> bool {
-    if i < 0 || j < 0 {
-        false
-    } else {
-        region.contains(&(i as usize, j as usize))
-    }
+/// Get the number of fence segments around a plot
+fn calculate_perimeter(labels: &Labels, i: usize, j: usize) -> usize {
+    let label = Some(labels[i][j]);
+    let (i, j) = (i as isize, j as isize);
+
+    [(1, 0), (-1, 0), (0, 1), (0, -1)]
+        .iter()
+        .filter(|(di, dj)| label_at(labels, i + di, j + dj) != label)
+        .count()
 }
 
-fn count_corners(i: usize, j: usize, region: &HashSet<(usize, usize)>) -> usize {
+/// Get the number of region corners at a plot, which equals the number of
+/// sides the plot contributes to
+fn count_corners(labels: &Labels, i: usize, j: usize) -> usize {
     let mut count = 0;
     let corners = [
         ((0, 1), (1, 0), (1, 1)),
@@ -43,12 +44,13 @@ fn count_corners(i: usize, j: usize, region: &HashSet<(usize, usize)>) -> usize
         ((-1, 0), (0, 1), (-1, 1)),
     ];
 
+    let label = Some(labels[i][j]);
     let (ix, jy) = (i as isize, j as isize);
 
     for ((dx0, dy0), (dx1, dy1), (dx2, dy2)) in &corners {
-        let c0 = cell_in_region(region, ix + dx0, jy + dy0);
-        let c1 = cell_in_region(region, ix + dx1, jy + dy1);
-        let c2 = cell_in_region(region, ix + dx2, jy + dy2);
+        let c0 = label_at(labels, ix + dx0, jy + dy0) == label;
+        let c1 = label_at(labels, ix + dx1, jy + dy1) == label;
+        let c2 = label_at(labels, ix + dx2, jy + dy2) == label;
 
         if !c0 && !c1 {
             count += 1;
@@ -62,80 +64,153 @@ fn count_corners(i: usize, j: usize, region: &HashSet<(usize, usize)>) -> usize
     count
 }
 
-fn calculate_sides(region: HashSet<(usize, usize)>) -> usize {
-    let mut sides = 0;
-
-    for &(i, j) in region.iter() {
-        sides += count_corners(i, j, &region);
-    }
-
-    sides
-}
-
 fn collect_map(input: &str) -> Vec<Vec<char>> {
     input.lines().map(|line| line.chars().collect()).collect()
 }
 
-fn collect_region(
-    map: &[Vec<char>],
-    visited: &mut Vec<(usize, usize)>,
-    i: usize,
-    j: usize,
-) -> HashSet<(usize, usize)> {
-    let char = map[i][j];
-    let mut region = HashSet::new();
-    let mut stack = vec![(i, j)];
-
-    while let Some((i, j)) = stack.pop() {
-        if i >= map.len() || j >= map[i].len() {
-            continue;
-        }
+/// Label connected plots of the same plant. Every plot is pushed onto the
+/// stack at most once, since it is labelled as soon as it is discovered.
+fn label_regions(map: &[Vec<char>]) -> (Labels, usize) {
+    let mut labels: Labels = map.iter().map(|row| vec![usize::MAX; row.len()]).collect();
+    let mut count = 0;
 
-        if map[i][j] != char {
-            continue;
-        }
+    for i in 0..map.len() {
+        for j in 0..map[i].len() {
+            if labels[i][j] != usize::MAX {
+                continue;
+            }
 
-        if region.contains(&(i, j)) {
-            continue;
-        }
+            let plant = map[i][j];
+            labels[i][j] = count;
+            let mut stack = vec![(i, j)];
+
+            while let Some((i, j)) = stack.pop() {
+                let neighbours = [
+                    (i.wrapping_add(1), j),
+                    (i.wrapping_sub(1), j),
+                    (i, j.wrapping_add(1)),
+                    (i, j.wrapping_sub(1)),
+                ];
+
+                for (ni, nj) in neighbours {
+                    if ni < map.len()
+                        && nj < map[ni].len()
+                        && map[ni][nj] == plant
+                        && labels[ni][nj] == usize::MAX
+                    {
+                        labels[ni][nj] = count;
+                        stack.push((ni, nj));
+                    }
+                }
+            }
 
-        if visited.contains(&(i, j)) {
-            continue;
+            count += 1;
         }
-
-        region.insert((i, j));
-        visited.push((i, j));
-        stack.push((i.wrapping_add(1), j));
-        stack.push((i.wrapping_sub(1), j));
-        stack.push((i, j.wrapping_add(1)));
-        stack.push((i, j.wrapping_sub(1)));
     }
 
-    region
+    (labels, count)
 }
 
-/// Collect the prices of each region
-fn collect_region_prices<F>(map: &[Vec<char>], calc_fn: F) -> Vec<usize>
-where
-    F: Fn(&HashSet<(usize, usize)>) -> usize,
-{
-    let mut visited: Vec<(usize, usize)> = Vec::new();
-    let mut prices = Vec::new();
+/// Label the map and measure every region in one pass over the plots
+fn collect_regions(map: &[Vec<char>]) -> (Labels, Vec<Region>) {
+    let (labels, count) = label_regions(map);
+    let mut regions: Vec<Option<Region>> = vec![None; count];
+
+    for (i, row) in labels.iter().enumerate() {
+        for (j, &label) in row.iter().enumerate() {
+            let region = regions[label].get_or_insert(Region {
+                plant: map[i][j],
+                area: 0,
+                perimeter: 0,
+                sides: 0,
+                bounds: ((i, j), (i, j)),
+            });
+
+            region.area += 1;
+            region.perimeter += calculate_perimeter(&labels, i, j);
+            region.sides += count_corners(&labels, i, j);
+
+            let ((top, left), (bottom, right)) = &mut region.bounds;
+            *top = (*top).min(i);
+            *left = (*left).min(j);
+            *bottom = (*bottom).max(i);
+            *right = (*right).max(j);
+        }
+    }
+
+    (labels, regions.into_iter().flatten().collect())
+}
 
-    for i in 0..map.len() {
-        for j in 0..map[i].len() {
-            if visited.contains(&(i, j)) {
-                continue;
+/// Every fence side as the region it belongs to and its two end corners.
+/// Neighbouring fence segments on the same side of the same region are
+/// joined, so there are as many sides per region as `count_corners` finds.
+fn fence_sides(labels: &Labels) -> Vec<(usize, (Cell, Cell))> {
+    let mut sides = Vec::new();
+    let rows = labels.len();
+    let cols = labels.first().map_or(0, Vec::len);
+
+    // Horizontal sides above (di = -1) and below (di = 1) each row
+    for di in [-1, 1] {
+        for i in 0..rows {
+            let has_fence =
+                |j: usize| label_at(labels, i as isize + di, j as isize) != Some(labels[i][j]);
+            let row = if di < 0 { i } else { i + 1 };
+            let mut j = 0;
+
+            while j < cols {
+                if !has_fence(j) {
+                    j += 1;
+                    continue;
+                }
+
+                let start = j;
+                while j + 1 < cols && labels[i][j + 1] == labels[i][start] && has_fence(j + 1) {
+                    j += 1;
+                }
+                sides.push((labels[i][start], ((row, start), (row, j + 1))));
+                j += 1;
             }
+        }
+    }
 
-            let region = collect_region(map, &mut visited, i, j);
-            prices.push(calc_fn(&region));
+    // Vertical sides left (dj = -1) and right (dj = 1) of each column
+    for dj in [-1, 1] {
+        for j in 0..cols {
+            let has_fence =
+                |i: usize| label_at(labels, i as isize, j as isize + dj) != Some(labels[i][j]);
+            let col = if dj < 0 { j } else { j + 1 };
+            let mut i = 0;
+
+            while i < rows {
+                if !has_fence(i) {
+                    i += 1;
+                    continue;
+                }
+
+                let start = i;
+                while i + 1 < rows && labels[i + 1][j] == labels[start][j] && has_fence(i + 1) {
+                    i += 1;
+                }
+                sides.push((labels[start][j], ((start, col), (i + 1, col))));
+                i += 1;
+            }
         }
     }
 
-    prices
+    sides
 }
 
+const REGION_COLORS: [(u8, u8, u8); 8] = [
+    (230, 120, 110),
+    (240, 190, 90),
+    (150, 200, 100),
+    (90, 190, 170),
+    (110, 150, 230),
+    (170, 120, 220),
+    (220, 130, 190),
+    (180, 170, 150),
+];
+
 pub struct Day12 {
     input: String,
 }
@@ -147,20 +222,49 @@ impl AdventDay for Day12 {
 
     fn part_one(&self) -> String {
         let map = collect_map(&self.input);
-        let prices = collect_region_prices(&map, |region| {
-            region.len() * calculate_perimeter(region.clone())
-        });
-        prices.iter().sum::<usize>().to_string()
+        let (_, regions) = collect_regions(&map);
+        regions
+            .iter()
+            .map(|region| region.area * region.perimeter)
+            .sum::<usize>()
+            .to_string()
     }
 
     fn part_two(&self) -> String {
         let map = collect_map(&self.input);
-        let prices = collect_region_prices(&map, |region| {
-            let area = region.len();
-            let sides = calculate_sides(region.clone());
-            area * sides
-        });
-        prices.iter().sum::<usize>().to_string()
+        let (_, regions) = collect_regions(&map);
+        regions
+            .iter()
+            .map(|region| region.area * region.sides)
+            .sum::<usize>()
+            .to_string()
+    }
+}
+
+impl Draw for Day12 {
+    /// Every region filled with a colour for its plant and outlined by its
+    /// fence sides.
+    fn svg(&self) -> Svg {
+        let map = collect_map(&self.input);
+        let (labels, regions) = collect_regions(&map);
+        let mut svg = Svg::new(map.len(), map.first().map_or(0, Vec::len), 16);
+
+        let mut plots = vec![Vec::new(); regions.len()];
+        for (i, row) in labels.iter().enumerate() {
+            for (j, &label) in row.iter().enumerate() {
+                plots[label].push((i, j));
+            }
+        }
+
+        for (region, plots) in regions.iter().zip(plots) {
+            let color = REGION_COLORS[region.plant as usize % REGION_COLORS.len()];
+            svg.cells(plots, color, 0.8);
+        }
+
+        let sides = fence_sides(&labels).into_iter().map(|(_, side)| side);
+        svg.edges(sides, (20, 20, 20), 2.0);
+
+        svg
     }
 }
 
@@ -231,4 +335,42 @@ EEEEE"#;
         let day12 = Day12::new(DATA_4.to_string());
         assert_eq!(day12.part_two(), "236");
     }
+
+    #[test]
+    fn collect_regions_labels_separate_regions() {
+        let map = collect_map(DATA_2);
+        let (labels, regions) = collect_regions(&map);
+
+        assert_eq!(regions.len(), 5);
+        assert_eq!(labels[0][0], labels[4][4]);
+        assert_ne!(labels[1][1], labels[1][3]);
+        assert_eq!(
+            regions[labels[0][0]],
+            Region {
+                plant: 'O',
+                area: 21,
+                perimeter: 36,
+                sides: 20,
+                bounds: ((0, 0), (4, 4)),
+            }
+        );
+        assert_eq!(regions[labels[3][3]].bounds, ((3, 3), (3, 3)));
+    }
+
+    #[test]
+    fn fence_sides_match_corner_count() {
+        for data in [DATA_1, DATA_2, DATA_3, DATA_4] {
+            let (labels, regions) = collect_regions(&collect_map(data));
+            let sides = fence_sides(&labels);
+
+            for (label, region) in regions.iter().enumerate() {
+                let count = sides.iter().filter(|&&(l, _)| l == label).count();
+                assert_eq!(count, region.sides);
+            }
+        }
+
+        let (labels, _) = collect_regions(&collect_map("AAA\nABA"));
+        assert!(fence_sides(&labels).contains(&(0, ((0, 0), (0, 3)))));
+        assert!(fence_sides(&labels).contains(&(1, ((1, 1), (2, 1)))));
+    }
 }
@@ -1,3 +1,4 @@
+use crate::utils::visualize::{Frame, Palette, Visualize};
 use crate::utils::AdventDay;
 use itertools::Itertools;
 // use std::collections::HashSet;
@@ -79,11 +80,8 @@ impl AdventDay for Day14 {
             //     break;
             // }
 
-            let str = grid_str(&robots, max_width, max_height);
-            for line in str.lines() {
-                if line.contains("########") {
-                    break 'outer;
-                }
+            if shows_tree(&robots, max_width, max_height) {
+                break 'outer;
             }
         }
 
@@ -91,6 +89,46 @@ impl AdventDay for Day14 {
     }
 }
 
+const PALETTE: &Palette = &[('#', (40, 200, 70)), ('.', (30, 30, 30))];
+
+impl Visualize for Day14 {
+    /// The robots every second until they draw the tree. The positions
+    /// repeat after `width * height` seconds, so that is as far as it goes
+    /// when there is no tree.
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame>> {
+        let mut robots = collect_robots(&self.input);
+        let (max_width, max_height) = get_dimensions(&robots);
+        let mut done = false;
+
+        Box::new((0..=max_width * max_height).map_while(move |second| {
+            if done {
+                return None;
+            }
+            if second > 0 {
+                for robot in robots.iter_mut() {
+                    robot.next(max_width, max_height);
+                }
+            }
+            done = shows_tree(&robots, max_width, max_height);
+
+            Some(Frame::parse(
+                &grid_str(&robots, max_width, max_height),
+                format!("second {}", second),
+            ))
+        }))
+    }
+
+    fn palette(&self) -> &'static Palette {
+        PALETTE
+    }
+}
+
+fn shows_tree(robots: &[Robot], max_width: i32, max_height: i32) -> bool {
+    grid_str(robots, max_width, max_height)
+        .lines()
+        .any(|line| line.contains("########"))
+}
+
 fn get_dimensions(robots: &[Robot]) -> (i32, i32) {
     let max_height = robots.iter().map(|r| r.p.1).max().unwrap() + 1;
     let max_width = robots.iter().map(|r| r.p.0).max().unwrap() + 1;
@@ -160,18 +198,14 @@ fn collect_robots_in_quadrants(
 }
 
 fn grid_str(robots: &[Robot], max_width: i32, max_height: i32) -> String {
-    let mut grid = String::new();
-
-    for y in 0..max_height {
-        for x in 0..max_width {
-            let is_robot = robots.iter().any(|r| r.p == (x, y));
-            let char = if is_robot { '#' } else { '.' };
-            grid.push(char);
-        }
-        grid.push('\n');
+    let mut grid = vec![vec!['.'; max_width as usize]; max_height as usize];
+    for robot in robots {
+        grid[robot.p.1 as usize][robot.p.0 as usize] = '#';
     }
 
-    grid
+    grid.iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
 }
 
 #[cfg(test)]
@@ -196,4 +230,22 @@ p=9,5 v=-3,-3"#;
         let day14 = Day14::new(DATA.to_string());
         assert_eq!(day14.part_one(), "12");
     }
+
+    #[test]
+    fn frames_stop_at_tree() {
+        let frames: Vec<Frame> = Day14::new(DATA.to_string()).frames().collect();
+        let start = Frame::parse(&grid_str(&collect_robots(DATA), 11, 7), "second 0");
+        assert_eq!(frames[0], start);
+        assert_eq!(frames.len(), 11 * 7 + 1);
+
+        // Eight robots on alternating rows that line up on the top row after one second.
+        let mut input: Vec<String> = (0..8)
+            .map(|x| format!("p={},{} v=0,-{}", x, x % 2 + 1, x % 2 + 1))
+            .collect();
+        input.push("p=10,6 v=0,0".to_string());
+        let frames: Vec<Frame> = Day14::new(input.join("\n")).frames().collect();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].caption, "second 1");
+        assert!(frames[1].tiles[0].starts_with(&['#'; 8]));
+    }
 }
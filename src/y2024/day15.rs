@@ -1,3 +1,4 @@
+use crate::utils::visualize::{Frame, Palette, Visualize};
 use crate::utils::AdventDay;
 use core::fmt;
 use itertools::Itertools;
@@ -210,6 +211,15 @@ impl Direction {
             _ => panic!("Invalid direction"),
         }
     }
+
+    fn to_char(self) -> char {
+        match self {
+            Self::Up => '^',
+            Self::Down => 'v',
+            Self::Left => '<',
+            Self::Right => '>',
+        }
+    }
 }
 
 pub struct Day15 {
@@ -243,6 +253,39 @@ impl AdventDay for Day15 {
     }
 }
 
+const PALETTE: &Palette = &[
+    ('#', (130, 130, 130)),
+    ('.', (50, 50, 50)),
+    ('O', (200, 140, 60)),
+    ('[', (200, 140, 60)),
+    (']', (200, 140, 60)),
+    ('@', (230, 50, 50)),
+];
+
+impl Visualize for Day15 {
+    /// The warehouse before the first move and after every move.
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame>> {
+        let (mut game, moves) = parse_input(&self.input);
+        let start = Frame::parse(&game.to_string(), "start");
+
+        Box::new(
+            std::iter::once(start).chain(moves.into_iter().enumerate().map(
+                move |(n, direction)| {
+                    game.move_robot(direction);
+                    Frame::parse(
+                        &game.to_string(),
+                        format!("move {}: {}", n + 1, direction.to_char()),
+                    )
+                },
+            )),
+        )
+    }
+
+    fn palette(&self) -> &'static Palette {
+        PALETTE
+    }
+}
+
 fn parse_input(input: &str) -> (Game, Moves) {
     let (game, moves) = input.split("\n\n").collect_tuple().unwrap();
     let game = parse_game(game);
@@ -336,4 +379,17 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^"#;
         let day15 = Day15::new(DATA_2.to_string());
         assert_eq!(day15.part_two(), "9021");
     }
+
+    #[test]
+    fn frames_show_every_move() {
+        let day15 = Day15::new(DATA.to_string());
+        let frames: Vec<Frame> = day15.frames().collect();
+
+        assert_eq!(frames.len(), 16);
+        assert_eq!(frames[1].caption, "move 1: <");
+        assert_eq!(
+            frames[15].to_string(),
+            "########\n#....OO#\n##.....#\n#.....O#\n#.#O@..#\n#...O..#\n#...O..#\n########\nmove 15: <"
+        );
+    }
 }
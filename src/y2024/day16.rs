@@ -1,7 +1,8 @@
+use crate::utils::svg::{Draw, Svg};
 use crate::utils::AdventDay;
 use std::{
     cmp::Ordering,
-    collections::{BinaryHeap, HashSet},
+    collections::{BinaryHeap, HashMap, HashSet},
 };
 
 #[derive(PartialEq, Clone, Copy)]
@@ -22,7 +23,6 @@ enum Direction {
 struct HeapItem {
     point: Point,
     score: usize,
-    path: Vec<Point>,
     prev_direction: Direction,
 }
 
@@ -40,6 +40,9 @@ impl PartialOrd for HeapItem {
 
 type Point = (usize, usize);
 type Map = Vec<Vec<Tile>>;
+type State = (Point, Direction);
+
+const DIRECTIONS: [Direction; 2] = [Direction::Vertical, Direction::Horizontal];
 
 pub struct Day16 {
     input: String,
@@ -54,8 +57,7 @@ impl AdventDay for Day16 {
         let map = parse_input(&self.input);
         let start = find_start(&map);
         let reindeer = find_reindeer(&map);
-        let best_paths = dfs_score(&map, start, reindeer);
-        let (score, _) = best_paths.first().unwrap();
+        let (score, _) = best_paths(&map, start, reindeer).unwrap();
 
         score.to_string()
     }
@@ -64,15 +66,42 @@ impl AdventDay for Day16 {
         let map = parse_input(&self.input);
         let start = find_start(&map);
         let reindeer = find_reindeer(&map);
-        let best_paths = dfs_score(&map, start, reindeer);
-        let mut sitting_points = HashSet::new();
-        for (_, path) in best_paths {
-            for point in path {
-                sitting_points.insert(point);
+        let (_, moves) = best_paths(&map, start, reindeer).unwrap();
+        let mut sitting_points: HashSet<Point> =
+            moves.iter().flat_map(|&(to, from)| [to, from]).collect();
+        sitting_points.insert(reindeer);
+
+        sitting_points.len().to_string()
+    }
+}
+
+impl Draw for Day16 {
+    /// The maze with every move that lies on a best path from the start to
+    /// the reindeer.
+    fn svg(&self) -> Svg {
+        let map = parse_input(&self.input);
+        let start = find_start(&map);
+        let reindeer = find_reindeer(&map);
+        let mut svg = Svg::new(map.len(), map.first().map_or(0, Vec::len), 16);
+
+        let walls = map.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|&(_, &tile)| tile == Tile::Wall)
+                .map(move |(x, _)| (y, x))
+        });
+        svg.cells(walls, (110, 110, 120), 1.0);
+
+        if let Some((_, moves)) = best_paths(&map, start, reindeer) {
+            for (to, from) in moves {
+                svg.path(&[(to.1, to.0), (from.1, from.0)], (240, 170, 50), 4.0, 0.6);
             }
         }
 
-        sitting_points.len().to_string()
+        svg.marker((start.1, start.0), (60, 200, 80))
+            .marker((reindeer.1, reindeer.0), (230, 70, 60));
+
+        svg
     }
 }
 
@@ -98,86 +127,111 @@ fn find_start(map: &Map) -> Point {
     panic!("Start not found");
 }
 
-fn dfs_score(map: &Map, start: Point, end: Point) -> Vec<(usize, Vec<Point>)> {
-    let mut best_paths = Vec::new();
-    let mut visited = HashSet::new();
-    let mut best_score = usize::MAX;
-
+/// The lowest score of every reachable state, starting from `start` facing
+/// east.
+fn best_scores(map: &Map, start: Point) -> HashMap<State, usize> {
+    let mut scores = HashMap::new();
     let mut heap = BinaryHeap::new();
     heap.push(HeapItem {
         point: start,
         score: 0,
-        path: vec![start].into_iter().collect(),
         prev_direction: Direction::Horizontal,
     });
 
-    // let mut visited: HashMap<(Point, Point), u32> = HashMap::new();
-
-    // let mut covered_tiles: HashSet<Point> = HashSet::new();
-
-    // let mut min_score = u32::MAX;
-    // bfs_queue.push_back(Tile {
-    //     point: starting_point,
-    //     direction: RIGHT,
-    //     score: 0,
-    //     previous_points: vec![starting_point],
-    // });
-
     while let Some(state) = heap.pop() {
         let HeapItem {
             point,
             score,
-            path,
             prev_direction,
         } = state;
 
-        if point == end {
-            if score < best_score {
-                best_score = score;
-                best_paths.clear();
-                best_paths.push((score, path.clone()));
-            } else if score == best_score {
-                best_paths.push((score, path.clone()));
-            }
-            continue;
-        }
-
-        if !visited.insert((point, prev_direction)) {
+        if scores.contains_key(&(point, prev_direction)) {
             continue;
         }
+        scores.insert((point, prev_direction), score);
 
-        let neighbors = [
-            (point.0 + 1, point.1),             // Right
-            (point.0.wrapping_sub(1), point.1), // Left
-            (point.0, point.1 + 1),             // Down
-            (point.0, point.1.wrapping_sub(1)), // Up
-        ];
-
-        for neighbor in neighbors {
+        for neighbor in neighbors(point) {
             if !is_valid_point(map, neighbor) {
                 continue;
             }
 
             let current_direction = determine_direction(point, neighbor);
-            let mut new_score = score + 1;
-
-            if prev_direction != current_direction {
-                new_score += 1000;
-            }
-
-            let mut new_path = path.clone();
-            new_path.insert(0, neighbor);
-
             heap.push(HeapItem {
                 point: neighbor,
-                score: new_score,
-                path: new_path,
+                score: score + step_score(prev_direction, current_direction),
                 prev_direction: current_direction,
             });
         }
     }
 
-    best_paths
+    scores
+}
+
+/// The best score from `start` to `end` and every move, as `(to, from)`,
+/// that lies on a path with that score. The moves are found by walking back
+/// from `end` over the moves whose scores add up exactly, so paths that share
+/// a state are all kept.
+fn best_paths(map: &Map, start: Point, end: Point) -> Option<(usize, Vec<(Point, Point)>)> {
+    let scores = best_scores(map, start);
+    let best_score = DIRECTIONS
+        .iter()
+        .filter_map(|&direction| scores.get(&(end, direction)))
+        .min()
+        .copied()?;
+
+    let mut stack: Vec<State> = DIRECTIONS
+        .iter()
+        .map(|&direction| (end, direction))
+        .filter(|state| scores.get(state) == Some(&best_score))
+        .collect();
+    let mut seen: HashSet<State> = stack.iter().copied().collect();
+    let mut moves = Vec::new();
+
+    while let Some((point, direction)) = stack.pop() {
+        let score = scores[&(point, direction)];
+
+        for prev in neighbors(point) {
+            if !is_valid_point(map, prev) || determine_direction(prev, point) != direction {
+                continue;
+            }
+
+            for prev_direction in DIRECTIONS {
+                let prev_state = (prev, prev_direction);
+                let Some(&prev_score) = scores.get(&prev_state) else {
+                    continue;
+                };
+                if prev_score + step_score(prev_direction, direction) != score {
+                    continue;
+                }
+
+                if !moves.contains(&(point, prev)) {
+                    moves.push((point, prev));
+                }
+                if seen.insert(prev_state) {
+                    stack.push(prev_state);
+                }
+            }
+        }
+    }
+
+    Some((best_score, moves))
+}
+
+fn neighbors(point: Point) -> [Point; 4] {
+    [
+        (point.0 + 1, point.1),             // Right
+        (point.0.wrapping_sub(1), point.1), // Left
+        (point.0, point.1 + 1),             // Down
+        (point.0, point.1.wrapping_sub(1)), // Up
+    ]
+}
+
+fn step_score(prev_direction: Direction, current_direction: Direction) -> usize {
+    if prev_direction == current_direction {
+        1
+    } else {
+        1001
+    }
 }
 
 fn is_valid_point(map: &Map, point: Point) -> bool {
@@ -261,16 +315,38 @@ mod tests {
     }
 
     #[test]
-    #[ignore = "not done"]
     fn part_two_example_one() {
         let day16 = Day16::new(DATA_1.to_string());
         assert_eq!(day16.part_two(), "45");
     }
 
     #[test]
-    #[ignore = "not done"]
     fn part_two_example_two() {
         let day16 = Day16::new(DATA_2.to_string());
         assert_eq!(day16.part_two(), "64");
     }
+
+    #[test]
+    fn svg_draws_best_path() {
+        let day16 = Day16::new(DATA_1.to_string());
+        let svg = day16.svg().to_string();
+
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="240" height="240""#)
+        );
+        assert!(svg.contains("<polyline points=\"216,24 "));
+        assert_eq!(svg.matches("<circle").count(), 2);
+    }
+
+    #[test]
+    fn svg_draws_every_best_path() {
+        let svg = Day16::new(DATA_1.to_string()).svg().to_string();
+        let tiles: HashSet<&str> = svg
+            .split("<polyline points=\"")
+            .skip(1)
+            .flat_map(|rest| rest[..rest.find('"').unwrap()].split(' '))
+            .collect();
+
+        assert_eq!(tiles.len(), 45);
+    }
 }
@@ -1,59 +1,485 @@
 use crate::utils::AdventDay;
-use core::panic;
-use std::{cmp::Reverse, collections::BinaryHeap};
+use std::{collections::HashSet, fmt, io};
 
-fn run(program: &[usize], a: usize, b: usize, c: usize) -> Vec<usize> {
-    let mut a = a;
-    let mut b = b;
-    let mut c = c;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Registers {
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+}
 
-    let mut pointer = 0;
-    let mut output: Vec<usize> = Vec::new();
+impl fmt::Display for Registers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "A={} B={} C={}", self.a, self.b, self.c)
+    }
+}
 
-    while pointer < program.len() {
-        let opcode = program[pointer];
-        let operand = program[pointer + 1];
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Opcode {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
 
-        if let Some(jumo) = step(&mut output, &mut a, &mut b, &mut c, opcode, operand) {
-            pointer = jumo;
-        } else {
-            pointer += 2;
+impl Opcode {
+    fn from_usize(opcode: usize) -> Option<Self> {
+        match opcode {
+            0 => Some(Opcode::Adv),
+            1 => Some(Opcode::Bxl),
+            2 => Some(Opcode::Bst),
+            3 => Some(Opcode::Jnz),
+            4 => Some(Opcode::Bxc),
+            5 => Some(Opcode::Out),
+            6 => Some(Opcode::Bdv),
+            7 => Some(Opcode::Cdv),
+            _ => None,
         }
     }
 
-    output
+    fn mnemonic(&self) -> &'static str {
+        match self {
+            Opcode::Adv => "adv",
+            Opcode::Bxl => "bxl",
+            Opcode::Bst => "bst",
+            Opcode::Jnz => "jnz",
+            Opcode::Bxc => "bxc",
+            Opcode::Out => "out",
+            Opcode::Bdv => "bdv",
+            Opcode::Cdv => "cdv",
+        }
+    }
 }
 
-fn step(
-    output: &mut Vec<usize>,
-    a: &mut usize,
-    b: &mut usize,
-    c: &mut usize,
-    opcode: usize,
-    operand: usize,
-) -> Option<usize> {
-    let get_operand = |operand| match operand {
-        0..=3 => operand,
-        4 => *a,
-        5 => *b,
-        6 => *c,
-        _ => panic!("Invalid operand"),
+fn combo_name(operand: usize) -> String {
+    match operand {
+        0..=3 => operand.to_string(),
+        4 => "A".to_string(),
+        5 => "B".to_string(),
+        6 => "C".to_string(),
+        _ => "<reserved>".to_string(),
+    }
+}
+
+/// Renders a single instruction, e.g. `bst A` or `bxl 5`. The operand of
+/// `bxc` is ignored by the machine, so it is left out, and an invalid opcode
+/// is shown as the raw word, e.g. `<9>`.
+fn format_instruction(opcode: usize, operand: usize) -> String {
+    let Some(opcode) = Opcode::from_usize(opcode) else {
+        return format!("<{}>", opcode);
+    };
+    let operand = match opcode {
+        Opcode::Bxl | Opcode::Jnz => operand.to_string(),
+        Opcode::Bxc => return opcode.mnemonic().to_string(),
+        _ => combo_name(operand),
+    };
+
+    format!("{} {}", opcode.mnemonic(), operand)
+}
+
+/// Describes what an instruction does in terms of the registers.
+fn describe_instruction(opcode: usize, operand: usize) -> String {
+    let combo = combo_name(operand);
+    let Some(known) = Opcode::from_usize(opcode) else {
+        return format!("invalid opcode {}", opcode);
     };
 
-    match opcode {
-        0 => *a /= 2usize.pow(get_operand(operand) as u32),
-        1 => *b ^= operand,
-        2 => *b = get_operand(operand) % 8,
-        3 => {
-            if *a != 0 {
-                return Some(operand);
+    match known {
+        Opcode::Adv => format!("A = A >> {}", combo),
+        Opcode::Bxl => format!("B = B ^ {}", operand),
+        Opcode::Bst => format!("B = {} % 8", combo),
+        Opcode::Jnz => format!("if A != 0 goto {}", operand),
+        Opcode::Bxc => "B = B ^ C".to_string(),
+        Opcode::Out => format!("output {} % 8", combo),
+        Opcode::Bdv => format!("B = A >> {}", combo),
+        Opcode::Cdv => format!("C = A >> {}", combo),
+    }
+}
+
+/// Disassembles a program into one instruction per line, prefixed with its
+/// address and followed by a short description of its effect.
+pub fn disassemble(program: &[usize]) -> String {
+    program
+        .chunks(2)
+        .enumerate()
+        .map(|(i, chunk)| match chunk {
+            [opcode, operand] => format!(
+                "{:>3}: {:<8} ; {}",
+                i * 2,
+                format_instruction(*opcode, *operand),
+                describe_instruction(*opcode, *operand)
+            ),
+            [dangling] => format!("{:>3}: {:<8} ; missing operand", i * 2, dangling),
+            _ => unreachable!(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceStep {
+    pub pointer: usize,
+    pub opcode: usize,
+    pub operand: usize,
+    pub before: Registers,
+    pub after: Registers,
+    pub output: Option<usize>,
+}
+
+impl fmt::Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>3}: {:<8} | {}",
+            self.pointer,
+            format_instruction(self.opcode, self.operand),
+            self.after
+        )?;
+        if let Some(value) = self.output {
+            write!(f, " | out {}", value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Halted,
+    Breakpoint(usize),
+    StepLimit,
+    InvalidOpcode { opcode: usize, pointer: usize },
+}
+
+/// The three-bit computer. Execution can be driven one instruction at a
+/// time with `step`, or with `resume` until it halts, hits a breakpoint or
+/// runs out of steps.
+pub struct Machine<'a> {
+    program: &'a [usize],
+    registers: Registers,
+    pointer: usize,
+    output: Vec<usize>,
+    breakpoints: HashSet<usize>,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(program: &'a [usize], registers: Registers) -> Self {
+        Self {
+            program,
+            registers,
+            pointer: 0,
+            output: Vec::new(),
+            breakpoints: HashSet::new(),
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, pointer: usize) {
+        self.breakpoints.insert(pointer);
+    }
+
+    pub fn registers(&self) -> Registers {
+        self.registers
+    }
+
+    pub fn output(&self) -> &[usize] {
+        &self.output
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.pointer + 1 >= self.program.len()
+    }
+
+    /// Executes the instruction at the current pointer, or returns `None` if
+    /// the machine has halted or the opcode is invalid.
+    pub fn step(&mut self) -> Option<TraceStep> {
+        if self.is_halted() {
+            return None;
+        }
+
+        let opcode = self.program[self.pointer];
+        let known = Opcode::from_usize(opcode)?;
+        let operand = self.program[self.pointer + 1];
+        let before = self.registers;
+        let Registers { a, b, c } = before;
+
+        // Literal operands may be 7, so combo operands are only resolved on use.
+        let combo = || match operand {
+            0..=3 => operand,
+            4 => a,
+            5 => b,
+            6 => c,
+            _ => panic!("Invalid combo operand {} at {}", operand, self.pointer),
+        };
+
+        let mut output = None;
+        let mut jump = None;
+        let mut after = before;
+        match known {
+            Opcode::Adv => after.a = shift(a, combo()),
+            Opcode::Bxl => after.b = b ^ operand,
+            Opcode::Bst => after.b = combo() % 8,
+            Opcode::Jnz if a != 0 => jump = Some(operand),
+            Opcode::Jnz => {}
+            Opcode::Bxc => after.b = b ^ c,
+            Opcode::Out => output = Some(combo() % 8),
+            Opcode::Bdv => after.b = shift(a, combo()),
+            Opcode::Cdv => after.c = shift(a, combo()),
+        }
+        self.registers = after;
+
+        let step = TraceStep {
+            pointer: self.pointer,
+            opcode,
+            operand,
+            before,
+            after: self.registers,
+            output,
+        };
+
+        self.output.extend(output);
+        self.pointer = jump.unwrap_or(self.pointer + 2);
+
+        Some(step)
+    }
+
+    /// Runs until the machine halts, reaches a breakpoint or an invalid
+    /// opcode, or has executed `step_limit` instructions. A breakpoint on the
+    /// current pointer is skipped, so calling `resume` again continues past it.
+    pub fn resume(
+        &mut self,
+        step_limit: Option<usize>,
+        mut on_step: impl FnMut(&TraceStep),
+    ) -> Stop {
+        let mut steps = 0;
+
+        loop {
+            if self.is_halted() {
+                return Stop::Halted;
+            }
+            if steps > 0 && self.breakpoints.contains(&self.pointer) {
+                return Stop::Breakpoint(self.pointer);
+            }
+            if step_limit.is_some_and(|limit| steps >= limit) {
+                return Stop::StepLimit;
+            }
+
+            let Some(step) = self.step() else {
+                return Stop::InvalidOpcode {
+                    opcode: self.program[self.pointer],
+                    pointer: self.pointer,
+                };
+            };
+            on_step(&step);
+            steps += 1;
+        }
+    }
+}
+
+/// `A >> n`, where shifting out every bit gives zero instead of overflowing.
+fn shift(value: usize, n: usize) -> usize {
+    value.checked_shr(n as u32).unwrap_or(0)
+}
+
+fn run(program: &[usize], a: usize, b: usize, c: usize) -> Vec<usize> {
+    let mut machine = Machine::new(program, Registers { a, b, c });
+    machine.resume(None, |_| {});
+
+    machine.output
+}
+
+/// Runs the program from `registers` and collects every executed step.
+pub fn trace(
+    program: &[usize],
+    registers: Registers,
+    breakpoints: &[usize],
+    step_limit: Option<usize>,
+) -> (Vec<TraceStep>, Stop) {
+    let mut machine = Machine::new(program, registers);
+    for &breakpoint in breakpoints {
+        machine.add_breakpoint(breakpoint);
+    }
+
+    let mut steps = Vec::new();
+    let stop = machine.resume(step_limit, |step| steps.push(*step));
+
+    (steps, stop)
+}
+
+/// Reasons the part two solver cannot handle a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuineError {
+    NotALoop,
+    InvalidOpcode { opcode: usize, pointer: usize },
+    InnerJump(usize),
+    ReservedOperand(usize),
+    Shift(&'static str),
+    CarriedRegister(char, usize),
+    NoOutput,
+    OutputLength { program: usize, per_loop: usize },
+    NoSolution,
+}
+
+impl fmt::Display for QuineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuineError::NotALoop => write!(f, "the program does not end with `jnz 0`"),
+            QuineError::InvalidOpcode { opcode, pointer } => {
+                write!(f, "invalid opcode {} at {}", opcode, pointer)
+            }
+            QuineError::InnerJump(pointer) => {
+                write!(f, "jump at {} inside the loop body", pointer)
+            }
+            QuineError::ReservedOperand(pointer) => {
+                write!(f, "reserved combo operand 7 at {}", pointer)
+            }
+            QuineError::Shift(reason) => write!(f, "{}", reason),
+            QuineError::CarriedRegister(register, pointer) => write!(
+                f,
+                "register {} is read at {} before it is written, so loop iterations are not independent",
+                register, pointer
+            ),
+            QuineError::NoOutput => write!(f, "the loop body never outputs"),
+            QuineError::OutputLength { program, per_loop } => write!(
+                f,
+                "the loop outputs {} values per iteration, which cannot make up a program of length {}",
+                per_loop, program
+            ),
+            QuineError::NoSolution => write!(f, "no value of A makes the program output itself"),
+        }
+    }
+}
+
+/// The shape of a program the solver supports: a single loop that ends with
+/// `jnz 0`, shifts A right by `shift` bits exactly once, outputs `outputs`
+/// values, and only reads B and C after writing them in the same iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LoopShape {
+    shift: u32,
+    outputs: usize,
+}
+
+fn analyse_loop(program: &[usize]) -> Result<LoopShape, QuineError> {
+    let len = program.len();
+    if len < 2 || !len.is_multiple_of(2) || program[len - 2..] != [3, 0] {
+        return Err(QuineError::NotALoop);
+    }
+
+    let mut shift = None;
+    let mut outputs = 0;
+    let mut written = [true, false, false];
+
+    for (i, instruction) in program[..len - 2].chunks(2).enumerate() {
+        let pointer = i * 2;
+        let Some(opcode) = Opcode::from_usize(instruction[0]) else {
+            return Err(QuineError::InvalidOpcode {
+                opcode: instruction[0],
+                pointer,
+            });
+        };
+        let operand = instruction[1];
+
+        let reads_combo = !matches!(opcode, Opcode::Bxl | Opcode::Jnz | Opcode::Bxc);
+        let mut reads = Vec::new();
+        if reads_combo {
+            match operand {
+                0..=3 => {}
+                4..=6 => reads.push(operand - 4),
+                _ => return Err(QuineError::ReservedOperand(pointer)),
             }
         }
-        4 => *b ^= *c,
-        5 => output.push(get_operand(operand) % 8),
-        6 => *b = *a / 2usize.pow(get_operand(operand) as u32),
-        7 => *c = *a / 2usize.pow(get_operand(operand) as u32),
-        _ => {}
+        match opcode {
+            Opcode::Bxl => reads.push(1),
+            Opcode::Bxc => reads.extend([1, 2]),
+            _ => {}
+        }
+        if let Some(&register) = reads.iter().find(|&&register| !written[register]) {
+            return Err(QuineError::CarriedRegister(
+                ['A', 'B', 'C'][register],
+                pointer,
+            ));
+        }
+
+        match opcode {
+            Opcode::Jnz => return Err(QuineError::InnerJump(pointer)),
+            Opcode::Adv if shift.is_some() => {
+                return Err(QuineError::Shift("A is shifted more than once per loop"))
+            }
+            Opcode::Adv if !(1..=3).contains(&operand) => {
+                return Err(QuineError::Shift(
+                    "A must be shifted by a literal 1, 2 or 3 per loop",
+                ))
+            }
+            Opcode::Adv => shift = Some(operand as u32),
+            Opcode::Out => outputs += 1,
+            Opcode::Bxl | Opcode::Bst | Opcode::Bxc | Opcode::Bdv => written[1] = true,
+            Opcode::Cdv => written[2] = true,
+        }
+    }
+
+    let shift = shift.ok_or(QuineError::Shift(
+        "A is never shifted, so the loop never ends",
+    ))?;
+    if outputs == 0 {
+        return Err(QuineError::NoOutput);
+    }
+    if !len.is_multiple_of(outputs) {
+        return Err(QuineError::OutputLength {
+            program: len,
+            per_loop: outputs,
+        });
+    }
+
+    Ok(LoopShape { shift, outputs })
+}
+
+/// Finds the smallest A for which the program outputs a copy of itself.
+///
+/// Iteration `i` of the loop starts with `A >> (shift * i)` and its output
+/// depends on nothing else, so A is built from its most significant chunk
+/// down. Each chunk is chosen by trying the candidates in increasing order
+/// and backtracking when no lower chunk can complete the output. The first
+/// complete candidate is therefore the minimum, and exhausting the search
+/// proves there is none.
+pub fn find_quine(program: &[usize]) -> Result<usize, QuineError> {
+    let shape = analyse_loop(program)?;
+    let body = &program[..program.len() - 2];
+    let iterations = program.len() / shape.outputs;
+
+    search_quine(program, body, shape, iterations - 1, 0).ok_or(QuineError::NoSolution)
+}
+
+fn search_quine(
+    program: &[usize],
+    body: &[usize],
+    shape: LoopShape,
+    iteration: usize,
+    higher: usize,
+) -> Option<usize> {
+    let expected = &program[iteration * shape.outputs..(iteration + 1) * shape.outputs];
+    let higher = higher.checked_mul(1 << shape.shift)?;
+
+    for chunk in 0..(1 << shape.shift) {
+        let a = higher | chunk;
+        // Every iteration after the first is only reached if A is non-zero.
+        if a == 0 && iteration > 0 {
+            continue;
+        }
+
+        if run(body, a, 0, 0) != expected {
+            continue;
+        }
+
+        if iteration == 0 {
+            return Some(a);
+        }
+
+        if let Some(a) = search_quine(program, body, shape, iteration - 1, a) {
+            return Some(a);
+        }
     }
 
     None
@@ -63,6 +489,100 @@ pub struct Day17 {
     input: String,
 }
 
+impl Day17 {
+    pub fn disassembly(&self) -> String {
+        let (_, program) = parse_data(&self.input);
+
+        disassemble(&program)
+    }
+
+    /// Traces the program from the input registers, one line per executed
+    /// instruction, followed by the reason execution stopped.
+    pub fn trace(&self, breakpoints: &[usize], step_limit: Option<usize>) -> String {
+        let (a, program) = parse_data(&self.input);
+        let (steps, stop) = trace(
+            &program,
+            Registers { a, b: 0, c: 0 },
+            breakpoints,
+            step_limit,
+        );
+
+        let mut lines: Vec<String> = steps.iter().map(|step| step.to_string()).collect();
+        lines.push(match stop {
+            Stop::Halted => "halted".to_string(),
+            Stop::Breakpoint(pointer) => format!("breakpoint at {}", pointer),
+            Stop::StepLimit => format!("step limit of {} reached", steps.len()),
+            Stop::InvalidOpcode { opcode, pointer } => {
+                format!("invalid opcode {} at {}", opcode, pointer)
+            }
+        });
+
+        lines.join("\n")
+    }
+
+    /// Interactive debugger reading commands from stdin.
+    pub fn debug(&self, breakpoints: &[usize]) {
+        let (a, program) = parse_data(&self.input);
+        let mut machine = Machine::new(&program, Registers { a, b: 0, c: 0 });
+        for &breakpoint in breakpoints {
+            machine.add_breakpoint(breakpoint);
+        }
+
+        println!("{}", disassemble(&program));
+        println!(
+            "Commands: s [n] step, c continue, b <addr> breakpoint, r registers, o output, q quit"
+        );
+
+        let print_step = |step: &TraceStep| println!("{}", step);
+        for line in io::stdin().lines() {
+            let line = line.unwrap();
+            let mut parts = line.split_whitespace();
+            let command = parts.next().unwrap_or("s");
+            let argument = parts.next().and_then(|arg| arg.parse::<usize>().ok());
+
+            match command {
+                "s" | "step" => {
+                    report_stop(machine.resume(Some(argument.unwrap_or(1)), print_step))
+                }
+                "c" | "continue" => report_stop(machine.resume(None, print_step)),
+                "b" | "break" => match argument {
+                    Some(pointer) => machine.add_breakpoint(pointer),
+                    None => println!("usage: b <addr>"),
+                },
+                "r" | "registers" => println!("{}", machine.registers()),
+                "o" | "output" => println!("{}", join_output(machine.output())),
+                "q" | "quit" => break,
+                _ => println!("unknown command {}", command),
+            }
+
+            if machine.is_halted() {
+                println!("halted with output {}", join_output(machine.output()));
+                break;
+            }
+        }
+    }
+}
+
+/// Tells the debugger user why stepping or continuing stopped early. Halting
+/// is reported by the command loop itself.
+fn report_stop(stop: Stop) {
+    match stop {
+        Stop::Breakpoint(pointer) => println!("breakpoint at {}", pointer),
+        Stop::InvalidOpcode { opcode, pointer } => {
+            println!("invalid opcode {} at {}", opcode, pointer)
+        }
+        Stop::Halted | Stop::StepLimit => {}
+    }
+}
+
+fn join_output(output: &[usize]) -> String {
+    output
+        .iter()
+        .map(|num| num.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
 impl AdventDay for Day17 {
     fn new(input: String) -> Self {
         Self { input }
@@ -73,37 +593,16 @@ impl AdventDay for Day17 {
 
         let output = run(&program, a, 0, 0);
 
-        output
-            .iter()
-            .map(|num| num.to_string())
-            .collect::<Vec<String>>()
-            .join(",")
+        join_output(&output)
     }
 
     fn part_two(&self) -> String {
         let (_, program) = parse_data(&self.input);
 
-        let mut heap: BinaryHeap<Reverse<usize>> = BinaryHeap::new();
-
-        for i in 1..8 {
-            heap.push(Reverse(i));
-        }
-
-        while let Some(Reverse(a)) = heap.pop() {
-            let output = run(&program, a, 0, 0);
-
-            if output == program {
-                return a.to_string();
-            }
-
-            if output == program[program.len() - output.len()..] {
-                for i in 0..8 {
-                    heap.push(Reverse((a << 3) + i));
-                }
-            }
+        match find_quine(&program) {
+            Ok(a) => a.to_string(),
+            Err(err) => format!("unsupported program: {}", err),
         }
-
-        panic!("No solution found")
     }
 }
 
@@ -121,9 +620,8 @@ fn parse_registers(register: &str) -> usize {
     register.lines().for_each(|line| {
         let (register, value) = line.split_once(": ").unwrap();
         let value = value.parse::<usize>().unwrap();
-        match register {
-            "Register A" => a = value,
-            _ => {}
+        if register == "Register A" {
+            a = value;
         }
     });
 
@@ -135,6 +633,7 @@ fn parse_instructions(program: &str) -> Vec<usize> {
         .split_once(": ")
         .unwrap()
         .1
+        .trim()
         .split(',')
         .map(|num| num.parse::<usize>().unwrap())
         .collect()
@@ -167,4 +666,109 @@ Program: 0,3,5,4,3,0"#;
         let day17 = Day17::new(DATA_2.to_string());
         assert_eq!(day17.part_two(), "117440");
     }
+
+    #[test]
+    fn disassemble_resolves_combo_operands() {
+        let day17 = Day17::new(DATA_2.to_string());
+        assert_eq!(
+            day17.disassembly(),
+            "  0: adv 3    ; A = A >> 3\n  2: out A    ; output A % 8\n  4: jnz 0    ; if A != 0 goto 0"
+        );
+    }
+
+    #[test]
+    fn trace_stops_at_breakpoint_and_step_limit() {
+        let program = [0, 1, 5, 4, 3, 0];
+        let registers = Registers { a: 729, b: 0, c: 0 };
+
+        let (steps, stop) = trace(&program, registers, &[4], None);
+        assert_eq!(stop, Stop::Breakpoint(4));
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].after.a, 364);
+        assert_eq!(steps[1].output, Some(4));
+        assert_eq!(
+            steps[1].to_string(),
+            "  2: out A    | A=364 B=0 C=0 | out 4"
+        );
+
+        let (steps, stop) = trace(&program, registers, &[], Some(5));
+        assert_eq!(stop, Stop::StepLimit);
+        assert_eq!(steps.len(), 5);
+    }
+
+    #[test]
+    fn invalid_opcodes_are_reported() {
+        let program = [0, 1, 9, 4, 3, 0];
+
+        assert_eq!(
+            disassemble(&program).lines().nth(1),
+            Some("  2: <9>      ; invalid opcode 9")
+        );
+
+        let (steps, stop) = trace(&program, Registers { a: 8, b: 0, c: 0 }, &[], None);
+        assert_eq!(steps.len(), 1);
+        assert_eq!(
+            stop,
+            Stop::InvalidOpcode {
+                opcode: 9,
+                pointer: 2
+            }
+        );
+
+        assert_eq!(
+            find_quine(&program),
+            Err(QuineError::InvalidOpcode {
+                opcode: 9,
+                pointer: 2
+            })
+        );
+    }
+
+    #[test]
+    fn machine_resumes_past_breakpoint() {
+        let program = [0, 1, 5, 4, 3, 0];
+        let mut machine = Machine::new(&program, Registers { a: 729, b: 0, c: 0 });
+        machine.add_breakpoint(0);
+
+        assert_eq!(machine.resume(None, |_| {}), Stop::Breakpoint(0));
+        assert_eq!(machine.output(), [4]);
+        assert_eq!(machine.resume(None, |_| {}), Stop::Breakpoint(0));
+        assert_eq!(machine.output(), [4, 6]);
+    }
+
+    #[test]
+    fn find_quine_for_real_program_shape() {
+        let program = [2, 4, 1, 1, 7, 5, 1, 5, 4, 0, 0, 3, 5, 5, 3, 0];
+        let a = find_quine(&program).unwrap();
+        assert_eq!(run(&program, a, 0, 0), program);
+        assert_ne!(run(&program, a - 1, 0, 0), program);
+    }
+
+    #[test]
+    fn find_quine_rejects_unsupported_programs() {
+        assert_eq!(find_quine(&[0, 3, 5, 4]), Err(QuineError::NotALoop));
+        assert_eq!(
+            find_quine(&[0, 3, 3, 0, 5, 4, 3, 0]),
+            Err(QuineError::InnerJump(2))
+        );
+        assert_eq!(
+            find_quine(&[1, 3, 0, 3, 5, 5, 3, 0]),
+            Err(QuineError::CarriedRegister('B', 0))
+        );
+        assert!(matches!(
+            find_quine(&[0, 4, 5, 4, 3, 0]),
+            Err(QuineError::Shift(_))
+        ));
+        assert_eq!(
+            find_quine(&[0, 3, 5, 4, 5, 4, 5, 4, 3, 0]),
+            Err(QuineError::OutputLength {
+                program: 10,
+                per_loop: 3
+            })
+        );
+        assert_eq!(
+            find_quine(&[0, 3, 5, 4, 5, 4, 3, 0]),
+            Err(QuineError::NoSolution)
+        );
+    }
 }
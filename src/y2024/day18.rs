@@ -1,119 +1,232 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::VecDeque;
 
+use crate::utils::svg::{Draw, Svg};
+use crate::utils::visualize::{Frame, Palette, Visualize};
 use crate::utils::AdventDay;
 
-type Grid<'a> = [[&'a str; 71]; 71];
+const GRID_SIZE: usize = 71;
+const BYTES_FALLEN: usize = 1024;
+
+/// For every cell, the index of the byte that corrupts it, or `usize::MAX`
+/// if no byte ever lands there. A cell is blocked after `n` bytes have fallen
+/// when its index is below `n`.
+type FallTimes = Vec<Vec<usize>>;
+
+fn parse_bytes(input: &str) -> Vec<(usize, usize)> {
+    input
+        .lines()
+        .map(|line| {
+            let (x, y) = line.split_once(",").unwrap();
+            (x.parse::<usize>().unwrap(), y.parse::<usize>().unwrap())
+        })
+        .collect()
+}
+
+/// Bytes that land outside the grid still count as fallen but block nothing.
+fn fall_times(bytes: &[(usize, usize)], size: usize) -> FallTimes {
+    let mut times = vec![vec![usize::MAX; size]; size];
+    for (i, &(x, y)) in bytes.iter().enumerate() {
+        if let Some(time) = times.get_mut(y).and_then(|row| row.get_mut(x)) {
+            *time = (*time).min(i);
+        }
+    }
+
+    times
+}
+
+fn find_shortest_path(times: &FallTimes, bytes_fallen: usize) -> Option<u32> {
+    find_path(times, bytes_fallen).map(|path| path.len() as u32 - 1)
+}
+
+/// One of the shortest paths from the start to the exit, both included.
+fn find_path(times: &FallTimes, bytes_fallen: usize) -> Option<Vec<(usize, usize)>> {
+    let size = times.len();
+    let is_open = |(x, y): (usize, usize)| times[y][x] >= bytes_fallen;
 
-fn find_shortest_path(grid: &Grid, is_test: bool) -> u32 {
     let start = (0, 0);
-    let end = if is_test { (6, 6) } else { (70, 70) };
-    let height_width = if is_test { 7 } else { 71 };
+    let end = (size - 1, size - 1);
+    if !is_open(start) || !is_open(end) {
+        return None;
+    }
 
-    let mut visited: HashSet<(u32, u32)> = HashSet::new();
-    let mut queue: VecDeque<((u32, u32), u32)> = VecDeque::new();
-    queue.push_back((start, 0));
+    let mut parent = vec![vec![None; size]; size];
+    let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
+    parent[start.1][start.0] = Some(start);
+    queue.push_back(start);
 
     let directions = [(0, 1), (0, -1), (1, 0), (-1, 0)];
 
-    let mut steps = u32::MAX;
-
-    while let Some(((x, y), step_count)) = queue.pop_front() {
+    while let Some((x, y)) = queue.pop_front() {
         if (x, y) == end {
-            steps = step_count;
-            break;
-        }
+            let mut path = vec![end];
+            while let Some(&(px, py)) = path.last().filter(|&&cell| cell != start) {
+                path.push(parent[py][px].unwrap());
+            }
+            path.reverse();
 
-        if visited.contains(&(x, y)) {
-            continue;
+            return Some(path);
         }
 
-        visited.insert((x, y));
-
         for &(dx, dy) in &directions {
-            let new_x = x as i32 + dx;
-            let new_y = y as i32 + dy;
-
-            if new_x >= 0
-                && new_x < height_width as i32
-                && new_y >= 0
-                && new_y < height_width as i32
-                && grid[new_y as usize][new_x as usize] != "#"
+            let (Some(new_x), Some(new_y)) = (x.checked_add_signed(dx), y.checked_add_signed(dy))
+            else {
+                continue;
+            };
+
+            if new_x < size
+                && new_y < size
+                && parent[new_y][new_x].is_none()
+                && is_open((new_x, new_y))
             {
-                queue.push_back(((new_x as u32, new_y as u32), step_count + 1));
+                parent[new_y][new_x] = Some((x, y));
+                queue.push_back((new_x, new_y));
             }
         }
     }
 
-    steps
+    None
+}
+
+/// Binary searches for the first byte that cuts off the exit. Reachability is
+/// monotonic in the number of fallen bytes, so only `log n` searches are needed.
+fn first_blocking_byte(bytes: &[(usize, usize)], size: usize) -> Option<(usize, usize)> {
+    let times = fall_times(bytes, size);
+    let (mut low, mut high) = (0, bytes.len());
+
+    if find_shortest_path(&times, high).is_some() {
+        return None;
+    }
+
+    // Invariant: the exit is reachable after `low` bytes and blocked after `high`.
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if find_shortest_path(&times, mid).is_some() {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    Some(bytes[high - 1])
 }
 
 pub struct Day18 {
     input: String,
+    grid_size: usize,
+    bytes_fallen: usize,
+}
+
+impl Day18 {
+    pub fn with_params(input: String, grid_size: usize, bytes_fallen: usize) -> Self {
+        Self {
+            input,
+            grid_size,
+            bytes_fallen,
+        }
+    }
 }
 
 impl AdventDay for Day18 {
     fn new(input: String) -> Self {
-        Self { input }
+        Self::with_params(input, GRID_SIZE, BYTES_FALLEN)
     }
 
     fn part_one(&self) -> String {
-        let corupted_bytes: Vec<(u32, u32)> = self
-            .input
-            .lines()
-            .map(|line| {
-                let (x, y) = line.split_once(",").unwrap();
-                (x.parse::<u32>().unwrap(), y.parse::<u32>().unwrap())
-            })
-            .collect();
-
-        let is_test = corupted_bytes.len() == 25;
-
-        let mut grid: Grid = [["."; 71]; 71];
-
-        let bytes_fallen = if is_test { 12 } else { 1024 };
-        for &(x, y) in corupted_bytes[0..bytes_fallen].iter() {
-            grid[y as usize][x as usize] = "#";
-        }
+        let bytes = parse_bytes(&self.input);
+        let times = fall_times(&bytes, self.grid_size);
+        let bytes_fallen = self.bytes_fallen.min(bytes.len());
 
-        find_shortest_path(&grid, is_test).to_string()
+        match find_shortest_path(&times, bytes_fallen) {
+            Some(steps) => steps.to_string(),
+            None => "no path".to_string(),
+        }
     }
 
     fn part_two(&self) -> String {
-        let corupted_bytes: Vec<(u32, u32)> = self
-            .input
-            .lines()
-            .map(|line| {
-                let (x, y) = line.split_once(",").unwrap();
-                (x.parse::<u32>().unwrap(), y.parse::<u32>().unwrap())
-            })
-            .collect();
+        let bytes = parse_bytes(&self.input);
 
-        let is_test = corupted_bytes.len() == 25;
+        match first_blocking_byte(&bytes, self.grid_size) {
+            Some((x, y)) => format!("{},{}", x, y),
+            None => "no blocking byte".to_string(),
+        }
+    }
+}
 
-        let mut grid: Grid = [["."; 71]; 71];
+impl Draw for Day18 {
+    /// The memory space after the first `bytes_fallen` bytes, with a
+    /// shortest path to the exit if there is one.
+    fn svg(&self) -> Svg {
+        let bytes = parse_bytes(&self.input);
+        let times = fall_times(&bytes, self.grid_size);
+        let bytes_fallen = self.bytes_fallen.min(bytes.len());
+        let mut svg = Svg::new(self.grid_size, self.grid_size, 12);
 
-        let mut bytes_fallen = if is_test { 12 } else { 1024 };
+        let corrupted = bytes[..bytes_fallen]
+            .iter()
+            .filter(|&&(x, y)| x < self.grid_size && y < self.grid_size)
+            .map(|&(x, y)| (y, x));
+        svg.cells(corrupted, (200, 60, 60), 1.0);
 
-        for &(x, y) in corupted_bytes[0..bytes_fallen].iter() {
-            grid[y as usize][x as usize] = "#";
+        if let Some(path) = find_path(&times, bytes_fallen) {
+            let cells: Vec<_> = path.iter().map(|&(x, y)| (y, x)).collect();
+            svg.path(&cells, (60, 200, 220), 4.0, 1.0);
         }
 
-        let mut steps = find_shortest_path(&grid, is_test);
-        let mut prevented_grid = (0, 0);
-
-        while steps != u32::MAX {
-            if bytes_fallen < corupted_bytes.len() {
-                let (x, y) = corupted_bytes[bytes_fallen];
-                bytes_fallen += 1;
-                grid[y as usize][x as usize] = "#";
-                steps = find_shortest_path(&grid, is_test);
-                prevented_grid = (x, y);
-            } else {
-                break;
+        svg
+    }
+}
+
+const PALETTE: &Palette = &[
+    ('#', (200, 60, 60)),
+    ('.', (40, 40, 40)),
+    ('O', (60, 200, 220)),
+];
+
+impl Visualize for Day18 {
+    /// The bytes falling one at a time with a shortest path around them,
+    /// until the exit is cut off. The path is only searched again when a
+    /// byte lands on it.
+    fn frames(&self) -> Box<dyn Iterator<Item = Frame>> {
+        let bytes = parse_bytes(&self.input);
+        let times = fall_times(&bytes, self.grid_size);
+        let mut tiles = vec![vec!['.'; self.grid_size]; self.grid_size];
+        let mut path = find_path(&times, 0);
+        let mut done = false;
+
+        Box::new((0..=bytes.len()).map_while(move |fallen| {
+            if done {
+                return None;
             }
-        }
 
-        format!("{},{}", prevented_grid.0, prevented_grid.1)
+            if let Some(&(x, y)) = fallen.checked_sub(1).map(|i| &bytes[i]) {
+                if let Some(tile) = tiles.get_mut(y).and_then(|row| row.get_mut(x)) {
+                    *tile = '#';
+                }
+                if path.as_ref().is_some_and(|path| path.contains(&(x, y))) {
+                    path = find_path(&times, fallen);
+                }
+            }
+
+            let mut frame = tiles.clone();
+            let caption = match &path {
+                Some(path) => {
+                    for &(x, y) in path {
+                        frame[y][x] = 'O';
+                    }
+                    format!("{} bytes fallen, {} steps", fallen, path.len() - 1)
+                }
+                None => {
+                    done = true;
+                    format!("{} bytes fallen, exit cut off", fallen)
+                }
+            };
+
+            Some(Frame::new(frame, caption))
+        }))
+    }
+
+    fn palette(&self) -> &'static Palette {
+        PALETTE
     }
 }
 
@@ -149,13 +262,49 @@ mod tests {
 
     #[test]
     fn part_one() {
-        let day18 = Day18::new(DATA.to_string());
+        let day18 = Day18::with_params(DATA.to_string(), 7, 12);
         assert_eq!(day18.part_one(), "22");
     }
 
     #[test]
     fn part_two() {
-        let day18 = Day18::new(DATA.to_string());
+        let day18 = Day18::with_params(DATA.to_string(), 7, 12);
         assert_eq!(day18.part_two(), "6,1");
     }
+
+    #[test]
+    fn uses_puzzle_size_by_default() {
+        let day18 = Day18::new(DATA.to_string());
+        assert_eq!(day18.part_one(), "146");
+        assert_eq!(day18.part_two(), "no blocking byte");
+    }
+
+    #[test]
+    fn skips_bytes_outside_grid() {
+        let input = "7,7\n0,1\n1,0".to_string();
+        assert_eq!(Day18::with_params(input.clone(), 7, 1).part_one(), "12");
+        assert_eq!(Day18::with_params(input.clone(), 7, 3).part_two(), "1,0");
+        assert_eq!(Day18::with_params(input, 7, 3).frames().count(), 4);
+    }
+
+    #[test]
+    fn frames_stop_when_exit_is_cut_off() {
+        let day18 = Day18::with_params(DATA.to_string(), 7, 12);
+        let frames: Vec<Frame> = day18.frames().collect();
+
+        assert_eq!(frames.len(), 22);
+        assert_eq!(frames[12].caption, "12 bytes fallen, 22 steps");
+        assert_eq!(frames[21].caption, "21 bytes fallen, exit cut off");
+        assert_eq!(frames[21].tiles[1][6], '#');
+    }
+
+    #[test]
+    fn svg_draws_bytes_and_path() {
+        let day18 = Day18::with_params(DATA.to_string(), 7, 12);
+        let svg = day18.svg().to_string();
+
+        assert_eq!(svg.matches("<rect x=").count(), 12);
+        assert!(svg.contains(r#"<polyline points="6,6 "#));
+        assert!(svg.contains(r#" 78,78" fill="none""#));
+    }
 }
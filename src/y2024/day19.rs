@@ -1,72 +1,214 @@
 use crate::utils::AdventDay;
 use std::collections::HashMap;
 
-pub struct Day19 {
-    input: String,
+/// Prefix tree over the towel patterns, so all towels that match at a
+/// position are found in one walk down the design.
+struct Trie<'a> {
+    nodes: Vec<TrieNode>,
+    towels: Vec<&'a str>,
 }
 
-impl AdventDay for Day19 {
-    fn new(input: String) -> Self {
-        Self { input }
+#[derive(Default)]
+struct TrieNode {
+    children: HashMap<u8, usize>,
+    towel: Option<usize>,
+}
+
+impl<'a> Trie<'a> {
+    fn new(towels: &[&'a str]) -> Self {
+        let mut nodes = vec![TrieNode::default()];
+
+        for (id, towel) in towels.iter().enumerate() {
+            let mut node = 0;
+            for &byte in towel.as_bytes() {
+                node = match nodes[node].children.get(&byte) {
+                    Some(&child) => child,
+                    None => {
+                        nodes.push(TrieNode::default());
+                        let child = nodes.len() - 1;
+                        nodes[node].children.insert(byte, child);
+                        child
+                    }
+                };
+            }
+            nodes[node].towel.get_or_insert(id);
+        }
+
+        Self {
+            nodes,
+            towels: towels.to_vec(),
+        }
     }
 
-    fn part_one(&self) -> String {
-        let (towels, desired_patterns) = parse_data(&self.input);
-        let mut count = 0;
-        let mut cache = HashMap::new();
+    /// Towels that `design` starts with, shortest first.
+    fn prefixes<'b>(&'b self, design: &'b [u8]) -> impl Iterator<Item = &'a str> + 'b {
+        design
+            .iter()
+            .scan(0, move |node, byte| {
+                *node = *self.nodes[*node].children.get(byte)?;
+                Some(self.nodes[*node].towel)
+            })
+            .flatten()
+            .map(move |id| self.towels[id])
+    }
+
+    /// Number of ways to make `design[i..]`, for every `i`.
+    fn count_arrangements(&self, design: &str) -> Vec<usize> {
+        let design = design.as_bytes();
+        let mut ways = vec![0; design.len() + 1];
+        ways[design.len()] = 1;
+
+        for i in (0..design.len()).rev() {
+            ways[i] = self
+                .prefixes(&design[i..])
+                .map(|towel| ways[i + towel.len()])
+                .sum();
+        }
+
+        ways
+    }
 
-        for pattern in desired_patterns {
-            let valid_patterns = count_valid_patterns(&mut cache, pattern.as_bytes(), &towels);
-            if valid_patterns > 0 {
-                count += 1;
+    /// The longest prefix of `design` that some combination of towels makes.
+    fn buildable_prefix(&self, design: &str) -> usize {
+        let design = design.as_bytes();
+        let mut reachable = vec![false; design.len() + 1];
+        reachable[0] = true;
+
+        for i in 0..design.len() {
+            if !reachable[i] {
+                continue;
+            }
+            for towel in self.prefixes(&design[i..]) {
+                reachable[i + towel.len()] = true;
             }
         }
 
-        count.to_string()
+        reachable.iter().rposition(|&r| r).unwrap()
     }
 
-    fn part_two(&self) -> String {
-        let (towels, desired_patterns) = parse_data(&self.input);
-        let mut count = 0;
-        let mut cache = HashMap::new();
+    /// Up to `limit` arrangements of `design`, in trie order. Branches that
+    /// cannot finish the design are never entered.
+    fn arrangements(&self, design: &str, limit: usize) -> Vec<Vec<&'a str>> {
+        let ways = self.count_arrangements(design);
+        let mut found = Vec::new();
+        let mut current = Vec::new();
+        self.collect_arrangements(design.as_bytes(), 0, &ways, limit, &mut current, &mut found);
+
+        found
+    }
 
-        for pattern in desired_patterns {
-            let valid_patterns = count_valid_patterns(&mut cache, pattern.as_bytes(), &towels);
-            count += valid_patterns;
+    fn collect_arrangements(
+        &self,
+        design: &[u8],
+        i: usize,
+        ways: &[usize],
+        limit: usize,
+        current: &mut Vec<&'a str>,
+        found: &mut Vec<Vec<&'a str>>,
+    ) {
+        if found.len() >= limit {
+            return;
         }
+        if i == design.len() {
+            found.push(current.clone());
+            return;
+        }
+
+        for towel in self.prefixes(&design[i..]) {
+            if ways[i + towel.len()] == 0 {
+                continue;
+            }
 
-        count.to_string()
+            current.push(towel);
+            self.collect_arrangements(design, i + towel.len(), ways, limit, current, found);
+            current.pop();
+        }
+    }
+
+    /// The `n`th arrangement of `design` in the order `arrangements` lists
+    /// them, found without listing the ones before it. Picking `n` at random
+    /// below the arrangement count samples arrangements uniformly.
+    fn nth_arrangement(&self, design: &str, mut n: usize) -> Option<Vec<&'a str>> {
+        let ways = self.count_arrangements(design);
+        if n >= ways[0] {
+            return None;
+        }
+
+        let design = design.as_bytes();
+        let mut arrangement = Vec::new();
+        let mut i = 0;
+
+        while i < design.len() {
+            for towel in self.prefixes(&design[i..]) {
+                let count = ways[i + towel.len()];
+                if n < count {
+                    arrangement.push(towel);
+                    i += towel.len();
+                    break;
+                }
+                n -= count;
+            }
+        }
+
+        Some(arrangement)
     }
 }
 
-fn count_valid_patterns<'a>(
-    cache: &mut HashMap<&'a [u8], usize>,
-    s: &'a [u8],
-    towels: &[&'a [u8]],
-) -> usize {
-    if s.is_empty() {
-        return 1;
+pub struct Day19 {
+    input: String,
+}
+
+impl Day19 {
+    /// Up to `limit` ways to make `design` from the available towels.
+    pub fn arrangements(&self, design: &str, limit: usize) -> Vec<Vec<&str>> {
+        let (towels, _) = parse_data(&self.input);
+        Trie::new(&towels).arrangements(design, limit)
     }
 
-    if let Some(&ans) = cache.get(&s) {
-        return ans;
+    pub fn nth_arrangement(&self, design: &str, n: usize) -> Option<Vec<&str>> {
+        let (towels, _) = parse_data(&self.input);
+        Trie::new(&towels).nth_arrangement(design, n)
     }
 
-    let mut num = 0;
-    for t in towels.iter() {
-        if s.starts_with(t) {
-            num += count_valid_patterns(cache, &s[t.len()..], towels);
-        }
+    /// How far into `design` the towels reach. An impossible design gets
+    /// stuck at this position.
+    pub fn buildable_prefix(&self, design: &str) -> usize {
+        let (towels, _) = parse_data(&self.input);
+        Trie::new(&towels).buildable_prefix(design)
+    }
+}
+
+impl AdventDay for Day19 {
+    fn new(input: String) -> Self {
+        Self { input }
     }
 
-    cache.insert(s, num);
+    fn part_one(&self) -> String {
+        let (towels, desired_patterns) = parse_data(&self.input);
+        let trie = Trie::new(&towels);
 
-    num
+        desired_patterns
+            .iter()
+            .filter(|pattern| trie.count_arrangements(pattern)[0] > 0)
+            .count()
+            .to_string()
+    }
+
+    fn part_two(&self) -> String {
+        let (towels, desired_patterns) = parse_data(&self.input);
+        let trie = Trie::new(&towels);
+
+        desired_patterns
+            .iter()
+            .map(|pattern| trie.count_arrangements(pattern)[0])
+            .sum::<usize>()
+            .to_string()
+    }
 }
 
-fn parse_data(input: &str) -> (Vec<&[u8]>, Vec<&str>) {
+fn parse_data(input: &str) -> (Vec<&str>, Vec<&str>) {
     let (towels, desired_patterns) = input.split_once("\n\n").unwrap();
-    let towels = towels.split(", ").map(|towel| towel.as_bytes()).collect();
+    let towels = towels.trim().split(", ").collect();
     let desired_patterns = desired_patterns.lines().collect();
 
     (towels, desired_patterns)
@@ -98,4 +240,31 @@ bbrgwb"#;
         let day19 = Day19::new(DATA.to_string());
         assert_eq!(day19.part_two(), "16");
     }
+
+    #[test]
+    fn arrangements() {
+        let day19 = Day19::new(DATA.to_string());
+
+        assert_eq!(
+            day19.arrangements("gbbr", 10),
+            [
+                vec!["g", "b", "b", "r"],
+                vec!["g", "b", "br"],
+                vec!["gb", "b", "r"],
+                vec!["gb", "br"],
+            ]
+        );
+        assert_eq!(day19.arrangements("rrbgbr", 2).len(), 2);
+        assert_eq!(day19.nth_arrangement("gbbr", 2), Some(vec!["gb", "b", "r"]));
+        assert_eq!(day19.nth_arrangement("gbbr", 4), None);
+    }
+
+    #[test]
+    fn buildable_prefix() {
+        let day19 = Day19::new(DATA.to_string());
+
+        assert_eq!(day19.buildable_prefix("ubwu"), 0);
+        assert_eq!(day19.buildable_prefix("bbrgwb"), 4);
+        assert_eq!(day19.buildable_prefix("brwrr"), 5);
+    }
 }